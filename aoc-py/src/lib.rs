@@ -0,0 +1,254 @@
+//! Python bindings for the day solvers.
+//!
+//! Build and install into the current virtualenv with `maturin develop`, then
+//! `from aoc_py import day4, day5, day7`.
+
+use std::{collections::HashSet, fmt::Display};
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+fn value_error(e: impl Display) -> PyErr {
+    PyValueError::new_err(format!("{e:#}"))
+}
+
+mod day4_py {
+    use super::*;
+
+    #[pyclass(name = "Map", module = "aoc_py.day4")]
+    pub struct Map(day4::Map);
+
+    #[pymethods]
+    impl Map {
+        #[staticmethod]
+        fn parse(input: &str) -> PyResult<Self> {
+            day4::Map::parse(input).map(Self).map_err(value_error)
+        }
+
+        #[getter]
+        fn width(&self) -> usize {
+            self.0.width
+        }
+
+        #[getter]
+        fn height(&self) -> usize {
+            self.0.height
+        }
+
+        /// the `(x, y)` coordinates of every location that can be accessed
+        fn find_accessible_locations(&self) -> HashSet<(usize, usize)> {
+            self.0
+                .find_accessible_locations()
+                .into_iter()
+                .map(|point| (point.x(), point.y()))
+                .collect()
+        }
+
+        /// removes locations until none are accessible, returning how many were
+        /// removed
+        fn part2(&mut self) -> usize {
+            self.0.part2()
+        }
+    }
+
+    pub fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
+        m.add_class::<Map>()
+    }
+}
+
+mod day5_py {
+    use super::*;
+
+    #[pyclass(name = "Database", module = "aoc_py.day5")]
+    pub struct Database(day5::Database);
+
+    #[pymethods]
+    impl Database {
+        #[staticmethod]
+        fn from_string(input: &str) -> PyResult<Self> {
+            day5::Database::from_string(input)
+                .map(Self)
+                .map_err(value_error)
+        }
+
+        fn fresh_ingredients(&self) -> Vec<u64> {
+            self.0.fresh_ingredients()
+        }
+    }
+
+    #[pyfunction]
+    fn part1(input: &str) -> PyResult<usize> {
+        day5::part1(input).map_err(value_error)
+    }
+
+    #[pyfunction]
+    fn part2(input: &str) -> PyResult<u64> {
+        day5::part2(input).map_err(value_error)
+    }
+
+    pub fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
+        m.add_class::<Database>()?;
+        m.add_function(wrap_pyfunction!(part1, m)?)?;
+        m.add_function(wrap_pyfunction!(part2, m)?)
+    }
+}
+
+mod day7_py {
+    use super::*;
+
+    #[pyclass(name = "Manifold", module = "aoc_py.day7")]
+    pub struct Manifold(day7::Manifold);
+
+    #[pymethods]
+    impl Manifold {
+        #[staticmethod]
+        fn parse(input: &str) -> PyResult<Self> {
+            day7::Manifold::parse(input).map(Self).map_err(value_error)
+        }
+
+        #[getter]
+        fn width(&self) -> i32 {
+            self.0.width()
+        }
+
+        #[getter]
+        fn height(&self) -> i32 {
+            self.0.height()
+        }
+
+        /// sends the beam through the manifold, returning the number of splits
+        fn run(&mut self) -> u64 {
+            self.0.run()
+        }
+
+        fn timelines(&mut self) -> u64 {
+            self.0.timelines()
+        }
+    }
+
+    #[pyfunction]
+    fn part1(input: &str) -> PyResult<u64> {
+        day7::Manifold::parse(input)
+            .map(|mut manifold| manifold.run())
+            .map_err(value_error)
+    }
+
+    #[pyfunction]
+    fn part2(input: &str) -> PyResult<u64> {
+        day7::Manifold::parse(input)
+            .map(|mut manifold| manifold.timelines())
+            .map_err(value_error)
+    }
+
+    pub fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
+        m.add_class::<Manifold>()?;
+        m.add_function(wrap_pyfunction!(part1, m)?)?;
+        m.add_function(wrap_pyfunction!(part2, m)?)
+    }
+}
+
+fn add_submodule(
+    parent: &Bound<'_, PyModule>,
+    name: &str,
+    init: fn(&Bound<'_, PyModule>) -> PyResult<()>,
+) -> PyResult<()> {
+    let py = parent.py();
+    let module = PyModule::new(py, name)?;
+    init(&module)?;
+    parent.add_submodule(&module)?;
+    // register the submodule so that `import aoc_py.day5` works too
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item(format!("aoc_py.{name}"), &module)
+}
+
+#[pymodule]
+fn aoc_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    add_submodule(m, "day4", day4_py::init)?;
+    add_submodule(m, "day5", day5_py::init)?;
+    add_submodule(m, "day7", day7_py::init)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pyo3::{types::PyDict, wrap_pymodule};
+
+    fn run_python(code: &std::ffi::CStr) {
+        Python::attach(|py| {
+            let module = wrap_pymodule!(aoc_py)(py);
+            // what importing the built extension would do
+            py.import("sys")
+                .and_then(|sys| sys.getattr("modules"))
+                .and_then(|modules| modules.set_item("aoc_py", &module))
+                .unwrap();
+            let locals = PyDict::new(py);
+            locals.set_item("aoc_py", module).unwrap();
+            if let Err(e) = py.run(code, None, Some(&locals)) {
+                e.print(py);
+                panic!("python code failed: {e}");
+            }
+        })
+    }
+
+    #[test]
+    fn test_day4() {
+        run_python(
+            c"
+map = aoc_py.day4.Map.parse('''..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.
+''')
+assert (map.width, map.height) == (10, 10)
+locs = map.find_accessible_locations()
+assert isinstance(locs, set)
+assert len(locs) == 13
+assert (2, 0) in locs
+assert map.part2() == 43
+",
+        );
+    }
+
+    #[test]
+    fn test_day5() {
+        run_python(
+            c"
+from aoc_py import day5
+db = day5.Database.from_string('3-5\\n10-14\\n16-20\\n12-18\\n\\n1\\n5\\n8\\n11\\n17\\n32\\n')
+assert db.fresh_ingredients() == [5, 11, 17]
+assert day5.part2('3-5\\n10-14\\n16-20\\n12-18\\n\\n1\\n') == 14
+try:
+    day5.Database.from_string('not a database')
+    raise AssertionError('expected a ValueError')
+except ValueError as e:
+    assert 'Unexpected input at line 1' in str(e)
+",
+        );
+    }
+
+    #[test]
+    fn test_day7() {
+        run_python(
+            c"
+import aoc_py.day7
+input = '.......S.......\\n...............\\n.......^.......\\n...............\\n......^.^......\\n...............'
+m = aoc_py.day7.Manifold.parse(input)
+assert (m.width, m.height) == (15, 6)
+assert m.run() == 3
+assert aoc_py.day7.Manifold.parse(input).timelines() == 4
+assert aoc_py.day7.part1(input) == 3
+try:
+    aoc_py.day7.Manifold.parse('...')
+    raise AssertionError('expected a ValueError')
+except ValueError as e:
+    assert 'start' in str(e)
+",
+        );
+    }
+}
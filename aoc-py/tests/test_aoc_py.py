@@ -0,0 +1,52 @@
+"""Run with `maturin develop && pytest` from the aoc-py directory"""
+
+from aoc_py import day4, day5, day7
+
+DAY4_INPUT = """..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.
+"""
+
+DAY5_INPUT = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n"
+
+DAY7_INPUT = """.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+..............."""
+
+
+def test_day4():
+    locations = day4.Map.parse(DAY4_INPUT).find_accessible_locations()
+    assert len(locations) == 13
+    assert all(isinstance(x, int) and isinstance(y, int) for (x, y) in locations)
+
+
+def test_day5():
+    database = day5.Database.from_string(DAY5_INPUT)
+    assert database.fresh_ingredients() == [5, 11, 17]
+    assert day5.part1(DAY5_INPUT) == 3
+    assert day5.part2(DAY5_INPUT) == 14
+
+
+def test_day7():
+    assert day7.Manifold.parse(DAY7_INPUT).run() == 21
+    assert day7.Manifold.parse(DAY7_INPUT).timelines() == 40
@@ -0,0 +1,189 @@
+//! Answers from earlier runs, so that a day that hasn't changed isn't solved
+//! again.
+//!
+//! Answers are keyed by the input alone. The runner always solves the puzzle
+//! as written and ignores the `[dayN]` options in `aoc.toml`, such as day1's
+//! dial, which only the day binaries read, so changing them can't make a
+//! cached answer stale
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, instrument};
+
+use crate::solvers::Solve;
+
+/// The answers for one day, all from the same build of its solver
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DayAnswers {
+    build: String,
+    /// keyed by [`key`]
+    answers: BTreeMap<String, String>,
+}
+
+/// Cached answers, stored as `<dir>/answers/day<day>.json`. Each file only
+/// keeps answers from the latest build of the day's solver, so rebuilding a
+/// day throws away everything cached for it
+#[derive(Debug)]
+pub struct AnswerCache {
+    dir: PathBuf,
+}
+
+/// The part and a hash of the input, identifying an answer within a day
+pub fn key(part: u32, input: &str) -> String {
+    let hash = Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<String>();
+    format!("part{part}:{hash}")
+}
+
+impl AnswerCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn path(&self, day: u32) -> PathBuf {
+        self.dir.join("answers").join(format!("day{day}.json"))
+    }
+
+    fn load(path: &Path) -> anyhow::Result<DayAnswers> {
+        if !path.exists() {
+            return Ok(DayAnswers::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read answers from {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse answers from {}", path.display()))
+    }
+
+    /// The answer cached for this input by the same build of `solver`
+    #[instrument(level = "debug", skip(self, solver, input), fields(day = solver.day()))]
+    pub fn get(
+        &self,
+        solver: &dyn Solve,
+        part: u32,
+        input: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let answers = Self::load(&self.path(solver.day()))?;
+        if answers.build != solver.build_id() {
+            debug!(
+                cached = answers.build,
+                "cached answers are from another build"
+            );
+            return Ok(None);
+        }
+        Ok(answers.answers.get(&key(part, input)).cloned())
+    }
+
+    /// Caches an answer, dropping any answers from other builds of `solver`
+    pub fn insert(
+        &self,
+        solver: &dyn Solve,
+        part: u32,
+        input: &str,
+        answer: &str,
+    ) -> anyhow::Result<()> {
+        let path = self.path(solver.day());
+        let build = solver.build_id();
+        let mut answers = Self::load(&path)?;
+        if answers.build != build {
+            answers = DayAnswers {
+                build,
+                answers: BTreeMap::default(),
+            };
+        }
+        answers.answers.insert(key(part, input), answer.to_string());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(&answers)?)
+            .with_context(|| format!("Failed to write answers to {}", path.display()))
+    }
+
+    /// Returns the cached answer, only solving if there isn't one
+    pub fn get_or_solve(
+        &self,
+        solver: &dyn Solve,
+        part: u32,
+        input: &str,
+    ) -> anyhow::Result<String> {
+        if let Some(answer) = self.get(solver, part, input)? {
+            debug!(answer, "using cached answer");
+            return Ok(answer);
+        }
+        let answer = solver.solve(part, input)?;
+        self.insert(solver, part, input, &answer)?;
+        Ok(answer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+    use test_log::test;
+
+    /// Counts how often it's asked to solve
+    struct Counter {
+        build: String,
+        solved: Cell<u32>,
+    }
+
+    impl Solve for Counter {
+        fn day(&self) -> u32 {
+            3
+        }
+
+        fn solve(&self, part: u32, input: &str) -> anyhow::Result<String> {
+            self.solved.set(self.solved.get() + 1);
+            Ok(format!("{part}:{}", input.len()))
+        }
+
+        fn build_id(&self) -> String {
+            self.build.clone()
+        }
+    }
+
+    #[test]
+    fn test_get_or_solve() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let cache = AnswerCache::new(dir.path());
+        let solver = Counter {
+            build: "1".to_string(),
+            solved: Cell::new(0),
+        };
+        assert_eq!("1:3", cache.get_or_solve(&solver, 1, "abc").unwrap());
+        assert_eq!("1:3", cache.get_or_solve(&solver, 1, "abc").unwrap());
+        assert_eq!(1, solver.solved.get());
+
+        // another part or input is another answer
+        assert_eq!("2:3", cache.get_or_solve(&solver, 2, "abc").unwrap());
+        assert_eq!("1:4", cache.get_or_solve(&solver, 1, "abcd").unwrap());
+        assert_eq!(3, solver.solved.get());
+        assert!(cache.path(3).exists());
+
+        // a rebuild invalidates everything for the day
+        let rebuilt = Counter {
+            build: "2".to_string(),
+            solved: Cell::new(0),
+        };
+        assert_eq!(None, cache.get(&rebuilt, 1, "abc").unwrap());
+        cache.get_or_solve(&rebuilt, 1, "abc").unwrap();
+        assert_eq!(1, rebuilt.solved.get());
+        assert_eq!(None, cache.get(&solver, 2, "abc").unwrap());
+    }
+
+    #[test]
+    fn test_key() {
+        assert_eq!(key(1, "abc"), key(1, "abc"));
+        assert_ne!(key(1, "abc"), key(2, "abc"));
+        assert_ne!(key(1, "abc"), key(1, "abd"));
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use tracing::{info, instrument, warn};
+
+use common::{metrics::Counters, progress::Limits};
+
+use crate::solvers::{Solve, solve_limited};
+
+/// The result of running a single part on a single input
+#[derive(Debug)]
+pub struct Outcome {
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+    /// the work the solver reported doing
+    pub counters: Counters,
+}
+
+/// Runs a solver, turning both errors and panics into a failed outcome so that
+/// one bad input can't abort a whole batch
+pub fn run_part(solve: impl Fn(&str) -> anyhow::Result<String>, input: &str) -> Outcome {
+    let start = Instant::now();
+    let (result, counters) =
+        common::metrics::measure(|| std::panic::catch_unwind(AssertUnwindSafe(|| solve(input))));
+    let elapsed = start.elapsed();
+    let answer = match result {
+        Ok(Ok(answer)) => Ok(answer),
+        Ok(Err(e)) => Err(format!("{e:#}")),
+        Err(payload) => Err(format!("panicked: {}", panic_message(payload.as_ref()))),
+    };
+    Outcome {
+        answer,
+        elapsed,
+        counters,
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[derive(Debug)]
+pub struct FileResult {
+    pub path: PathBuf,
+    pub part1: Outcome,
+    pub part2: Outcome,
+}
+
+/// Solves both parts for every file in `dir`, in filename order
+#[instrument(level = "debug", skip(solver, limits))]
+pub fn run_batch(
+    solver: &dyn Solve,
+    dir: &Path,
+    limits: &Limits,
+) -> anyhow::Result<Vec<FileResult>> {
+    let mut paths = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+    paths.sort();
+    let results = paths
+        .into_iter()
+        .map(|path| {
+            info!(?path, "solving");
+            match std::fs::read_to_string(&path) {
+                Ok(input) => FileResult {
+                    part1: run_part(|input| solve_limited(solver, 1, input, limits), &input),
+                    part2: run_part(|input| solve_limited(solver, 2, input, limits), &input),
+                    path,
+                },
+                Err(e) => {
+                    warn!(?path, "failed to read input");
+                    let failed = || Outcome {
+                        answer: Err(format!("failed to read input: {e}")),
+                        elapsed: Duration::ZERO,
+                        counters: Counters::new(),
+                    };
+                    FileResult {
+                        part1: failed(),
+                        part2: failed(),
+                        path,
+                    }
+                }
+            }
+        })
+        .collect();
+    Ok(results)
+}
+
+/// Renders the results as a plain text table with one row per file
+pub fn render_table(results: &[FileResult]) -> String {
+    let header = ["file", "part 1", "time", "work", "part 2", "time", "work"].map(String::from);
+    let answer = |outcome: &Outcome| match &outcome.answer {
+        Ok(answer) => answer.clone(),
+        // keep multi-line errors, like lint reports, on a single row
+        Err(e) => format!(
+            "ERROR: {}",
+            e.lines().map(str::trim).collect::<Vec<_>>().join(" ")
+        ),
+    };
+    let rows = results
+        .iter()
+        .map(|result| {
+            [
+                result
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                answer(&result.part1),
+                format!("{:?}", result.part1.elapsed),
+                common::metrics::render(&result.part1.counters),
+                answer(&result.part2),
+                format!("{:?}", result.part2.elapsed),
+                common::metrics::render(&result.part2.counters),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let mut widths = header.clone().map(|h| h.len());
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let format_row = |row: &[String; 7]| {
+        row.iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+    let mut output = format_row(&header);
+    output.push('\n');
+    output.push_str(
+        &widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    );
+    output.push('\n');
+    for row in rows.iter() {
+        output.push_str(&format_row(row));
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solvers::solver;
+    use test_log::test;
+
+    const DAY6_INPUT: &str = "123 328  51 64
+ 45 64  387 23
+  6 98  215 314
+*   +   *   +
+";
+
+    #[test]
+    fn test_run_part() {
+        let outcome = run_part(
+            |_| {
+                common::metrics::count("steps", 3);
+                Ok("42".to_string())
+            },
+            "",
+        );
+        assert_eq!(Ok("42".to_string()), outcome.answer);
+        assert_eq!(Counters::from([("steps", 3)]), outcome.counters);
+        let outcome = run_part(|_| Err(anyhow::anyhow!("bad input")), "");
+        assert_eq!(Err("bad input".to_string()), outcome.answer);
+        let outcome = run_part(|_| panic!("oh no"), "");
+        assert_eq!(Err("panicked: oh no".to_string()), outcome.answer);
+    }
+
+    #[test]
+    fn test_batch() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        std::fs::write(dir.path().join("alice"), DAY6_INPUT).unwrap();
+        // bob's editor strips trailing whitespace
+        let trimmed = DAY6_INPUT
+            .lines()
+            .map(|l| l.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(dir.path().join("bob"), trimmed).unwrap();
+        std::fs::write(dir.path().join("carol"), "1 2\n3 4\n/ -\n").unwrap();
+
+        let results =
+            run_batch(solver(6).unwrap(), dir.path(), &Limits::default()).expect("batch failed");
+        assert_eq!(3, results.len());
+        for result in &results[..2] {
+            assert_eq!(Ok("4277556".to_string()), result.part1.answer);
+            assert_eq!(Ok("3263827".to_string()), result.part2.answer);
+        }
+        assert!(results[2].part1.answer.is_err());
+        assert!(results[2].part2.answer.is_err());
+
+        let table = render_table(&results);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(5, lines.len());
+        assert!(lines[0].starts_with("file "));
+        assert!(lines[2].starts_with("alice"));
+        assert!(lines[4].starts_with("carol"));
+        assert!(lines[4].contains("ERROR"));
+    }
+}
@@ -0,0 +1,216 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, anyhow};
+use tracing::{debug, info, instrument};
+
+use crate::{config::Config, submit::Verdict};
+
+const USER_AGENT: &str = "github.com/jonner/advent2025 aoc-client";
+
+/// A minimal client for the puzzle server. All requests are made relative to
+/// `base_url` so that tests can point it at a local server.
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl std::fmt::Debug for Client {
+    // don't leak the session token into logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .user_agent(USER_AGENT)
+            .build()
+            .into();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(&config.base_url, &config.session)
+    }
+
+    fn url(&self, year: u32, day: u32, endpoint: &str) -> String {
+        format!("{}/{year}/day/{day}/{endpoint}", self.base_url)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    #[instrument(level = "debug")]
+    pub fn fetch_input(&self, year: u32, day: u32) -> anyhow::Result<String> {
+        let url = self.url(year, day, "input");
+        info!(url, "downloading input");
+        let mut response = self
+            .agent
+            .get(&url)
+            .header("Cookie", self.cookie())
+            .call()
+            .with_context(|| format!("Request to {url} failed"))?;
+        let status = response.status();
+        let body = response.body_mut().read_to_string()?;
+        if !status.is_success() {
+            return Err(anyhow!(
+                "Failed to download input for {year} day {day}: {status}: {}",
+                body.trim()
+            ));
+        }
+        Ok(body)
+    }
+
+    #[instrument(level = "debug")]
+    pub fn submit_answer(
+        &self,
+        year: u32,
+        day: u32,
+        part: u32,
+        answer: &str,
+    ) -> anyhow::Result<Verdict> {
+        let url = self.url(year, day, "answer");
+        info!(url, answer, "submitting answer");
+        let level = part.to_string();
+        let mut response = self
+            .agent
+            .post(&url)
+            .header("Cookie", self.cookie())
+            .send_form([("level", level.as_str()), ("answer", answer)])
+            .with_context(|| format!("Request to {url} failed"))?;
+        let status = response.status();
+        let body = response.body_mut().read_to_string()?;
+        if !status.is_success() {
+            return Err(anyhow!(
+                "Failed to submit answer for {year} day {day}: {status}: {}",
+                body.trim()
+            ));
+        }
+        Ok(Verdict::from_response(&body))
+    }
+}
+
+/// Downloaded inputs, stored as `<dir>/<year>/day<day>.input`
+#[derive(Debug)]
+pub struct InputCache {
+    dir: PathBuf,
+}
+
+impl InputCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("day{day}.input"))
+    }
+
+    /// Returns the cached input, only downloading it if it has never been
+    /// fetched before
+    #[instrument(level = "debug")]
+    pub fn get_or_fetch(&self, client: &Client, year: u32, day: u32) -> anyhow::Result<String> {
+        let path = self.path(year, day);
+        if path.exists() {
+            debug!(?path, "using cached input");
+            return std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read cached input {}", path.display()));
+        }
+        let input = client.fetch_input(year, day)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &input)
+            .with_context(|| format!("Failed to write cached input {}", path.display()))?;
+        Ok(input)
+    }
+}
+
+/// The path (relative to the repository root) that each day's `main` reads its
+/// input from
+pub fn day_input_path(root: &Path, day: u32) -> PathBuf {
+    let filename = match day {
+        1 => "part1.input",
+        _ => "input",
+    };
+    root.join(format!("day{day}")).join(filename)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+    use test_log::test;
+
+    #[test]
+    fn test_day_input_path() {
+        let root = Path::new("/src/advent");
+        assert_eq!(
+            PathBuf::from("/src/advent/day1/part1.input"),
+            day_input_path(root, 1)
+        );
+        assert_eq!(
+            PathBuf::from("/src/advent/day7/input"),
+            day_input_path(root, 7)
+        );
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let server = MockServer::start();
+        server.respond("GET", "/2025/day/3/input", 200, "12345\n67890\n");
+        let client = Client::new(&server.url(), "secret");
+        let input = client.fetch_input(2025, 3).expect("Failed to fetch");
+        assert_eq!("12345\n67890\n", input);
+
+        let requests = server.requests();
+        assert_eq!(1, requests.len());
+        assert_eq!("GET", requests[0].method);
+        assert_eq!("/2025/day/3/input", requests[0].path);
+        assert_eq!(Some("session=secret".to_string()), requests[0].cookie);
+        assert!(requests[0].body.is_empty());
+    }
+
+    #[test]
+    fn test_fetch_input_error() {
+        let server = MockServer::start();
+        server.respond("GET", "/2025/day/3/input", 400, "Please log in");
+        let client = Client::new(&server.url(), "bad");
+        let err = client.fetch_input(2025, 3).expect_err("Expected failure");
+        assert!(err.to_string().contains("Please log in"));
+    }
+
+    #[test]
+    fn test_cache_only_fetches_once() {
+        let server = MockServer::start();
+        server.respond("GET", "/2025/day/5/input", 200, "3-5\n\n4\n");
+        let client = Client::new(&server.url(), "secret");
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let cache = InputCache::new(dir.path());
+
+        let first = cache
+            .get_or_fetch(&client, 2025, 5)
+            .expect("Failed to fetch");
+        let second = cache
+            .get_or_fetch(&client, 2025, 5)
+            .expect("Failed to fetch");
+        assert_eq!(first, second);
+        assert_eq!(1, server.requests().len());
+        assert!(dir.path().join("2025").join("day5.input").exists());
+
+        // a different year is a different puzzle
+        assert!(cache.get_or_fetch(&client, 2024, 5).is_err());
+        assert_eq!(2, server.requests().len());
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Deserialize;
+use tracing::{debug, instrument};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// User settings for talking to the puzzle server, normally read from
+/// `~/.config/aoc/config.toml`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// value of the `session` cookie from a logged-in browser
+    pub session: String,
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    /// where downloaded inputs are cached. Defaults to the user's cache
+    /// directory
+    pub cache_dir: Option<PathBuf>,
+}
+
+fn default_base_url() -> String {
+    DEFAULT_BASE_URL.to_string()
+}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("aoc").join("config.toml"))
+    }
+
+    #[instrument(level = "debug")]
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let config = Self::from_string(&contents)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+        debug!(base_url = config.base_url, "loaded config");
+        Ok(config)
+    }
+
+    pub fn from_string(input: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(input)?)
+    }
+
+    /// Where things are cached if the config doesn't say otherwise
+    pub fn default_cache_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("aoc"))
+    }
+
+    pub fn cache_dir(&self) -> anyhow::Result<PathBuf> {
+        self.cache_dir
+            .clone()
+            .or_else(Self::default_cache_dir)
+            .ok_or_else(|| anyhow::anyhow!("Unable to determine a cache directory"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::from_string("session = \"abc123\"").expect("Failed to parse");
+        assert_eq!("abc123", config.session);
+        assert_eq!(DEFAULT_BASE_URL, config.base_url);
+        assert_eq!(None, config.cache_dir);
+
+        let config = Config::from_string(
+            "session = \"abc123\"
+base_url = \"http://127.0.0.1:8080\"
+cache_dir = \"/tmp/aoc\"",
+        )
+        .expect("Failed to parse");
+        assert_eq!("http://127.0.0.1:8080", config.base_url);
+        assert_eq!(Some(PathBuf::from("/tmp/aoc")), config.cache_dir);
+    }
+
+    #[test]
+    fn test_parse_missing_session() {
+        assert!(Config::from_string("base_url = \"http://localhost\"").is_err());
+    }
+}
@@ -0,0 +1,404 @@
+//! Records the simulation-style days one step at a time so they can be
+//! replayed in an interactive terminal viewer
+
+use std::{collections::HashSet, ops::ControlFlow};
+
+use anyhow::anyhow;
+use common::stepper;
+use ratatui::{
+    DefaultTerminal,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Paragraph},
+};
+
+/// The state of a simulation after a single step
+#[derive(Debug)]
+pub struct Frame {
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Recording {
+    pub frames: Vec<Frame>,
+}
+
+impl Recording {
+    fn push(&mut self, title: impl Into<String>, body: impl Into<String>) {
+        self.frames.push(Frame {
+            title: title.into(),
+            body: body.into(),
+        });
+    }
+}
+
+/// Runs the simulation for the given day and part, recording every step
+pub fn record(day: u32, part: u32, input: &str) -> anyhow::Result<Recording> {
+    match (day, part) {
+        (1, _) => record_dial(input),
+        (4, _) => record_map(input),
+        (7, 1) => record_beams(input),
+        (7, 2) => record_timelines(input),
+        _ => Err(anyhow!(
+            "Day {day} part {part} isn't a step-by-step simulation"
+        )),
+    }
+}
+
+/// Draws the dial as a ruler with a marker under the current position
+fn render_dial(dial: &day1::Dial, position: i32) -> String {
+    let ruler = (0..dial.size())
+        .map(|n| match n % 10 {
+            0 => '|',
+            _ => '.',
+        })
+        .collect::<String>();
+    let labels = (0..dial.size())
+        .step_by(10)
+        .map(|n| format!("{n:<10}"))
+        .collect::<String>();
+    let marker = format!("{:>width$}", "^", width = position as usize + 1);
+    format!("{labels}\n{ruler}\n{marker} {position}\n")
+}
+
+fn record_dial(input: &str) -> anyhow::Result<Recording> {
+    let rotations = day1::parse(input)?;
+    let mut recording = Recording::default();
+    let dial = day1::Dial::default();
+    let mut state = day1::State2::with_dial(dial);
+    let (mut landings, mut passes) = (0, 0);
+    let mut before = state.0;
+    recording.push("start", render_dial(&dial, before));
+    let mut observer = |i: usize,
+                        rotating: &day1::Rotating<day1::State2, _>,
+                        crossings: &day1::Count| {
+        let position = rotating.state().0;
+        passes += crossings;
+        if position == dial.target() {
+            landings += 1;
+        }
+        recording.push(
+            format!(
+                "rotation {}: {:?}, {before} -> {position}",
+                i + 1,
+                rotating.last().expect("a rotation was applied")
+            ),
+            format!(
+                "{}\npassed zero {crossings} times this step\nlanded on zero: {landings} (part 1)\npassed zero: {passes} (part 2)\n",
+                render_dial(&dial, position)
+            ),
+        );
+        before = position;
+        ControlFlow::Continue(())
+    };
+    stepper::run(
+        &mut day1::Rotating::new(&mut state, rotations),
+        &mut observer,
+    );
+    Ok(recording)
+}
+
+fn record_map(input: &str) -> anyhow::Result<Recording> {
+    let mut map = day4::Map::parse(input)?;
+    let mut recording = Recording::default();
+    let mut total = 0;
+    recording.push("start", map.render(&HashSet::default()));
+    let mut observer = |round: usize, map: &day4::Map, removed: &HashSet<day4::Point>| {
+        total += removed.len();
+        recording.push(
+            format!(
+                "round {}: removing {} accessible locations",
+                round + 1,
+                removed.len()
+            ),
+            format!("{}\nremoved so far: {total}\n", map.render(removed)),
+        );
+        ControlFlow::Continue(())
+    };
+    stepper::run(&mut map, &mut observer);
+    recording.push("finished", map.render(&HashSet::default()));
+    Ok(recording)
+}
+
+fn record_beams(input: &str) -> anyhow::Result<Recording> {
+    let mut manifold = day7::Manifold::parse(input)?;
+    let mut recording = Recording::default();
+    let mut total = 0;
+    let mut observer = |row: usize, manifold: &day7::Manifold, splits: &u64| {
+        total += splits;
+        recording.push(
+            format!(
+                "row {row}: {} beams, {splits} splits",
+                manifold.beams().len()
+            ),
+            format!(
+                "{}\ntotal splits: {total}\n",
+                manifold.render(manifold.beams())
+            ),
+        );
+        ControlFlow::Continue(())
+    };
+    stepper::run(&mut manifold, &mut observer);
+    Ok(recording)
+}
+
+fn record_timelines(input: &str) -> anyhow::Result<Recording> {
+    let manifold = day7::Manifold::parse(input)?;
+    let mut recording = Recording::default();
+    let mut record = |row: usize, timelines: &day7::Timelines| {
+        let ntimelines = timelines.count();
+        recording.push(
+            format!("row {row}: {ntimelines} timelines"),
+            format!(
+                "{}\ntimelines: {ntimelines}\n",
+                manifold.render(timelines.data.keys())
+            ),
+        );
+    };
+    let mut propagation = manifold.propagate();
+    record(0, propagation.timelines());
+    let mut observer = |row: usize, propagation: &day7::Propagation, _: &u64| {
+        record(row + 1, propagation.timelines());
+        ControlFlow::Continue(())
+    };
+    stepper::run(&mut propagation, &mut observer);
+    Ok(recording)
+}
+
+/// A cursor into a recording
+#[derive(Debug)]
+pub struct Player<'a> {
+    recording: &'a Recording,
+    step: usize,
+    scroll: u16,
+}
+
+impl<'a> Player<'a> {
+    pub fn new(recording: &'a Recording) -> Self {
+        Self {
+            recording,
+            step: 0,
+            scroll: 0,
+        }
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn len(&self) -> usize {
+        self.recording.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recording.frames.is_empty()
+    }
+
+    pub fn current(&self) -> Option<&Frame> {
+        self.recording.frames.get(self.step)
+    }
+
+    pub fn jump(&mut self, step: usize) {
+        self.step = step.min(self.len().saturating_sub(1));
+    }
+
+    pub fn forward(&mut self) {
+        self.jump(self.step + 1);
+    }
+
+    pub fn back(&mut self) {
+        self.jump(self.step.saturating_sub(1));
+    }
+
+    pub fn first(&mut self) {
+        self.jump(0);
+    }
+
+    pub fn last(&mut self) {
+        self.jump(usize::MAX);
+    }
+}
+
+/// Shows the recording in the terminal until the user quits
+pub fn run(recording: &Recording) -> anyhow::Result<()> {
+    if recording.frames.is_empty() {
+        return Err(anyhow!("Nothing was recorded"));
+    }
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, Player::new(recording));
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, mut player: Player) -> anyhow::Result<()> {
+    // digits typed after pressing `g`
+    let mut jump: Option<String> = None;
+    loop {
+        terminal.draw(|frame| draw(frame, &player, jump.as_deref()))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if let Some(digits) = jump.as_mut() {
+            match key.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => digits.push(c),
+                KeyCode::Backspace => {
+                    digits.pop();
+                }
+                KeyCode::Enter => {
+                    if let Ok(step) = digits.parse() {
+                        player.jump(step);
+                    }
+                    jump = None;
+                }
+                KeyCode::Esc => jump = None,
+                _ => {}
+            }
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('n') | KeyCode::Char(' ') => {
+                player.forward()
+            }
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('p') => player.back(),
+            KeyCode::Home => player.first(),
+            KeyCode::End => player.last(),
+            KeyCode::Char('g') => jump = Some(String::new()),
+            KeyCode::Down | KeyCode::Char('j') => player.scroll = player.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => player.scroll = player.scroll.saturating_sub(1),
+            _ => {}
+        }
+    }
+}
+
+fn draw(frame: &mut ratatui::Frame, player: &Player, jump: Option<&str>) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let Some(current) = player.current() else {
+        return;
+    };
+    frame.render_widget(
+        Line::from(format!(
+            "step {}/{}: {}",
+            player.step(),
+            player.len() - 1,
+            current.title
+        ))
+        .bold(),
+        header,
+    );
+    frame.render_widget(
+        Paragraph::new(current.body.as_str())
+            .block(Block::bordered())
+            .scroll((player.scroll, 0)),
+        body,
+    );
+    let help = match jump {
+        Some(digits) => format!("jump to step: {digits}"),
+        None => "←/→ step  home/end first/last  g jump  ↑/↓ scroll  q quit".to_string(),
+    };
+    frame.render_widget(Line::from(help).dim(), footer);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    const DAY1_INPUT: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    #[test]
+    fn test_record_dial() {
+        let recording = record(1, 1, DAY1_INPUT).expect("Failed to record");
+        assert_eq!(11, recording.frames.len());
+        assert_eq!("rotation 1: Left(68), 50 -> 82", recording.frames[1].title);
+        let last = &recording.frames[10].body;
+        assert!(last.contains("landed on zero: 3 (part 1)"));
+        assert!(last.contains("passed zero: 6 (part 2)"));
+    }
+
+    #[test]
+    fn test_render_dial() {
+        let dial = render_dial(&day1::Dial::default(), 3);
+        let lines = dial.lines().collect::<Vec<_>>();
+        assert_eq!(100, lines[1].len());
+        assert_eq!("   ^ 3", lines[2]);
+    }
+
+    #[test]
+    fn test_record_map() {
+        let input = "..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.
+";
+        let recording = record(4, 2, input).expect("Failed to record");
+        assert_eq!(
+            "round 1: removing 13 accessible locations",
+            recording.frames[1].title
+        );
+        let last_round = &recording.frames[recording.frames.len() - 2];
+        assert!(last_round.body.contains("removed so far: 43"));
+    }
+
+    #[test]
+    fn test_record_day7() {
+        let input = ".......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............";
+        let recording = record(7, 1, input).expect("Failed to record");
+        assert_eq!(6, recording.frames.len());
+        assert!(recording.frames[5].body.contains("total splits: 3"));
+
+        let recording = record(7, 2, input).expect("Failed to record");
+        assert_eq!("row 0: 1 timelines", recording.frames[0].title);
+        assert!(
+            recording.frames[recording.frames.len() - 1]
+                .title
+                .ends_with("4 timelines")
+        );
+        assert!(record(5, 1, input).is_err());
+    }
+
+    #[test]
+    fn test_player() {
+        let recording = record(1, 1, DAY1_INPUT).expect("Failed to record");
+        let mut player = Player::new(&recording);
+        assert_eq!(0, player.step());
+        player.back();
+        assert_eq!(0, player.step());
+        player.forward();
+        player.forward();
+        assert_eq!(2, player.step());
+        player.back();
+        assert_eq!(1, player.step());
+        player.jump(7);
+        assert_eq!(7, player.step());
+        player.jump(100);
+        assert_eq!(10, player.step());
+        player.first();
+        assert_eq!(0, player.step());
+        player.last();
+        assert_eq!(10, player.step());
+        assert_eq!(recording.frames[10].title, player.current().unwrap().title);
+    }
+}
@@ -0,0 +1,131 @@
+//! Collects the reasoning steps that the solvers narrate with
+//! `info!(target: "explain", ...)` so that an answer can be explained rather
+//! than just printed
+//!
+//! Only the built-in solvers can be explained, since plugins log through their
+//! own copy of `tracing`
+
+use std::{
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+use tracing::{
+    Event, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, Registry, layer::Context, prelude::*};
+
+use crate::solvers::Solve;
+
+/// The tracing target that solvers use for explanation events
+pub const TARGET: &str = "explain";
+
+/// An answer along with the steps taken to reach it
+#[derive(Debug)]
+pub struct Explanation {
+    pub answer: String,
+    pub steps: Vec<String>,
+}
+
+impl Explanation {
+    /// Renders the steps as a numbered list followed by the answer
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        if self.steps.is_empty() {
+            output.push_str("(no steps were recorded for this solver)\n");
+        }
+        let width = self.steps.len().to_string().len();
+        for (i, step) in self.steps.iter().enumerate() {
+            let _ = writeln!(output, "{:>width$}. {step}", i + 1);
+        }
+        let _ = writeln!(output, "answer: {}", self.answer);
+        output
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0 = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{value:?}");
+        }
+    }
+}
+
+/// A layer that keeps the message of every explanation event
+#[derive(Clone, Default)]
+struct Collector {
+    steps: Arc<Mutex<Vec<String>>>,
+}
+
+impl<S: Subscriber> Layer<S> for Collector {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != TARGET {
+            return;
+        }
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        self.steps
+            .lock()
+            .expect("explanation collector poisoned")
+            .push(visitor.0);
+    }
+}
+
+/// Solves a part while recording every explanation event the solver emits
+pub fn explain(solver: &dyn Solve, part: u32, input: &str) -> anyhow::Result<Explanation> {
+    let collector = Collector::default();
+    let subscriber = Registry::default().with(collector.clone());
+    let answer = tracing::subscriber::with_default(subscriber, || solver.solve(part, input))?;
+    let steps = std::mem::take(&mut *collector.steps.lock().expect("collector poisoned"));
+    Ok(Explanation { answer, steps })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solvers::solver;
+    use test_log::test;
+
+    #[test]
+    fn test_explain_day1() {
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+        let explanation = explain(solver(1).unwrap(), 2, input).expect("Failed to solve");
+        assert_eq!("6", explanation.answer);
+        assert!(!explanation.steps.is_empty());
+        let rendered = explanation.render();
+        assert!(rendered.starts_with("1. rotation 1 reached the target after 50 clicks"));
+        assert!(rendered.ends_with("answer: 6\n"));
+    }
+
+    #[test]
+    fn test_explain_day5() {
+        let input = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+        let explanation = explain(solver(5).unwrap(), 2, input).expect("Failed to solve");
+        assert_eq!("14", explanation.answer);
+        assert!(
+            explanation
+                .steps
+                .iter()
+                .any(|step| step.contains("merged into"))
+        );
+
+        let explanation = explain(solver(5).unwrap(), 1, input).expect("Failed to solve");
+        assert_eq!("3", explanation.answer);
+        assert_eq!(6, explanation.steps.len());
+    }
+
+    #[test]
+    fn test_explain_error() {
+        assert!(explain(solver(5).unwrap(), 1, "nonsense").is_err());
+    }
+}
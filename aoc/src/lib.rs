@@ -0,0 +1,16 @@
+pub mod answers;
+pub mod batch;
+pub mod client;
+pub mod config;
+pub mod debugger;
+pub mod explain;
+pub mod plugins;
+pub mod server;
+pub mod solvers;
+pub mod submit;
+
+#[cfg(test)]
+pub(crate) mod mock;
+
+/// The puzzle year that this repository solves
+pub const YEAR: u32 = 2025;
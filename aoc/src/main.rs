@@ -0,0 +1,380 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use aoc::{
+    YEAR,
+    answers::AnswerCache,
+    batch,
+    client::{Client, InputCache, day_input_path},
+    config::Config,
+    debugger, explain,
+    plugins::Plugins,
+    server,
+    solvers::{self, Solve},
+    submit::GuessLog,
+};
+use clap::{Args, Parser, Subcommand};
+use common::{
+    logging::LogArgs,
+    progress::{Limits, Update},
+    settings::Settings,
+};
+
+#[derive(Debug, Parser)]
+#[command(about = "Advent of Code helper")]
+struct Cli {
+    /// path to the config file holding the session token
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// directory of day plugins to use in place of the built-in solvers
+    #[arg(long, global = true)]
+    plugins: Option<PathBuf>,
+    /// project settings file to use instead of searching for `aoc.toml`
+    #[arg(long, global = true)]
+    settings: Option<PathBuf>,
+    #[command(flatten)]
+    logging: LogArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Args)]
+struct LimitArgs {
+    /// give up on any phase (linting, or solving a part) that takes longer
+    /// than this many seconds
+    #[arg(long, value_name = "SECONDS", value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+    /// report the progress of long-running solvers on stderr
+    #[arg(long)]
+    progress: bool,
+}
+
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    let seconds = s.parse::<f64>().map_err(|e| e.to_string())?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| e.to_string())
+}
+
+/// how often progress is printed
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+impl LimitArgs {
+    fn limits(&self) -> Limits {
+        let progress = self.progress.then(|| {
+            let last = Mutex::new(None::<Instant>);
+            Arc::new(move |update: &Update<'_>| {
+                let mut last = last.lock().expect("progress poisoned");
+                if last.is_some_and(|last| last.elapsed() < PROGRESS_INTERVAL) {
+                    return;
+                }
+                *last = Some(Instant::now());
+                let total = update.total.map(|t| format!("/{t}")).unwrap_or_default();
+                eprintln!(
+                    "{}: {} {}{total} ({:.2?})",
+                    update.phase, update.task, update.done, update.elapsed
+                );
+            }) as common::progress::Callback
+        });
+        Limits {
+            timeout: self.timeout,
+            progress,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Download a day's puzzle input into the day's directory
+    Download {
+        day: u32,
+        #[arg(long, default_value_t = YEAR)]
+        year: u32,
+        /// root of the repository checkout, used if `aoc.toml` doesn't give
+        /// the day's input
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+    /// Submit an answer, computing it from the day's solver unless one is given
+    Submit {
+        day: u32,
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: u32,
+        /// submit this answer instead of solving the puzzle
+        #[arg(long)]
+        answer: Option<String>,
+        /// input file to solve. Defaults to the day's input
+        #[arg(long)]
+        input: Option<PathBuf>,
+        #[arg(long, default_value_t = YEAR)]
+        year: u32,
+        /// root of the repository checkout, used if `aoc.toml` doesn't give
+        /// the day's input
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+    /// Solve a day's puzzle, optionally explaining how the answer was reached
+    Solve {
+        day: u32,
+        /// the part to solve. Both parts are solved if not given
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: Option<u32>,
+        /// input file to solve. Defaults to the day's input
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// root of the repository checkout, used if `aoc.toml` doesn't give
+        /// the day's input
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+        /// narrate the steps that led to each answer
+        #[arg(long)]
+        explain: bool,
+        /// solve even if the answer is already cached
+        #[arg(long)]
+        no_cache: bool,
+        /// report how much work the solver did. Implies --no-cache
+        #[arg(long)]
+        metrics: bool,
+        #[command(flatten)]
+        limits: LimitArgs,
+    },
+    /// Check a day's input for structural problems without solving it
+    Lint {
+        day: u32,
+        /// input file to check. Defaults to the day's input
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// root of the repository checkout, used if `aoc.toml` doesn't give
+        /// the day's input
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+    /// Solve both parts for every input file in a directory
+    Batch {
+        day: u32,
+        /// directory containing one input file per person
+        dir: PathBuf,
+        #[command(flatten)]
+        limits: LimitArgs,
+    },
+    /// Step through a simulation interactively
+    Debug {
+        day: u32,
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=2), default_value_t = 1)]
+        part: u32,
+        /// input file to simulate. Defaults to the day's input
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// root of the repository checkout, used if `aoc.toml` doesn't give
+        /// the day's input
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+    /// List the plugins found in the `--plugins` directory
+    Plugins,
+    /// Solve inputs posted to `/day/{n}/part/{p}` on a local HTTP server
+    Serve {
+        #[arg(long, default_value_t = server::DEFAULT_PORT)]
+        port: u16,
+    },
+}
+
+fn load_config(path: &Option<PathBuf>) -> anyhow::Result<Config> {
+    let path = path
+        .clone()
+        .or_else(Config::default_path)
+        .ok_or_else(|| anyhow!("Unable to determine config file location"))?;
+    Config::load(&path)
+}
+
+/// The cache directory from the config file, or the default one if there is
+/// no config file
+fn cache_dir(path: &Option<PathBuf>) -> anyhow::Result<PathBuf> {
+    match load_config(path) {
+        Ok(config) => config.cache_dir(),
+        Err(_) => Config::default_cache_dir()
+            .ok_or_else(|| anyhow!("Unable to determine a cache directory")),
+    }
+}
+
+fn load_settings(cli: &Cli) -> anyhow::Result<Settings> {
+    let mut settings = match &cli.settings {
+        Some(path) => Settings::load(path)?,
+        None => Settings::discover()?,
+    };
+    cli.logging.apply(&mut settings.logging);
+    Ok(settings)
+}
+
+/// The input given on the command line, then the one from the settings, then
+/// the day's input under `root`
+fn input_path(
+    settings: &Settings,
+    input: Option<PathBuf>,
+    root: &Path,
+    day: u32,
+) -> anyhow::Result<PathBuf> {
+    Ok(match input {
+        Some(input) => input,
+        None => settings
+            .day(day)?
+            .input
+            .unwrap_or_else(|| day_input_path(root, day)),
+    })
+}
+
+fn load_plugins(dir: &Option<PathBuf>) -> anyhow::Result<Plugins> {
+    let Some(dir) = dir else {
+        return Ok(Plugins::default());
+    };
+    let plugins = Plugins::discover(dir)?;
+    for (path, e) in plugins.failed.iter() {
+        eprintln!("Skipping plugin {}: {e:#}", path.display());
+    }
+    Ok(plugins)
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let settings = load_settings(&cli)?;
+    let _logging = settings.logging.init()?;
+    let plugins = load_plugins(&cli.plugins)?;
+    match cli.command {
+        Command::Download { day, year, root } => {
+            let config = load_config(&cli.config)?;
+            let client = Client::from_config(&config);
+            let cache = InputCache::new(config.cache_dir()?);
+            let input = cache.get_or_fetch(&client, year, day)?;
+            let path = input_path(&settings, None, &root, day)?;
+            std::fs::write(&path, input)?;
+            println!("Wrote {}", path.display());
+        }
+        Command::Submit {
+            day,
+            part,
+            answer,
+            input,
+            year,
+            root,
+        } => {
+            let config = load_config(&cli.config)?;
+            let answer = match answer {
+                Some(answer) => answer,
+                None => {
+                    let path = input_path(&settings, input, &root, day)?;
+                    let input = std::fs::read_to_string(&path)?;
+                    let cache = AnswerCache::new(config.cache_dir()?);
+                    cache.get_or_solve(solvers::select(day, &plugins)?, part, &input)?
+                }
+            };
+            let mut guesses = GuessLog::load(&config.cache_dir()?, year, day, part)?;
+            if let Err(refusal) = guesses.check(&answer) {
+                return Err(anyhow!("Refusing to submit {answer}: {refusal}"));
+            }
+            let client = Client::from_config(&config);
+            let verdict = client.submit_answer(year, day, part, &answer)?;
+            println!("Day {day} part {part}: {answer} is {verdict}");
+            guesses.record(&answer, verdict);
+            guesses.save()?;
+        }
+        Command::Solve {
+            day,
+            part,
+            input,
+            root,
+            explain,
+            no_cache,
+            metrics,
+            limits,
+        } => {
+            let path = input_path(&settings, input, &root, day)?;
+            let input = std::fs::read_to_string(&path)?;
+            let solver = solvers::select(day, &plugins)?;
+            if explain && plugins.get(day).is_some() {
+                return Err(anyhow!(
+                    "Day {day} is loaded from a plugin and can't be explained"
+                ));
+            }
+            let cache = AnswerCache::new(cache_dir(&cli.config)?);
+            let parts = part.map_or(vec![1, 2], |part| vec![part]);
+            for part in parts {
+                if explain {
+                    let explanation = explain::explain(solver, part, &input)?;
+                    println!("Day {day} part {part}:");
+                    print!("{}", explanation.render());
+                } else {
+                    let cached = match no_cache || metrics {
+                        true => None,
+                        false => cache.get(solver, part, &input)?,
+                    };
+                    let (answer, counters) = match cached {
+                        Some(answer) => (answer, Default::default()),
+                        None => {
+                            let (answer, counters) = common::metrics::measure(|| {
+                                solvers::solve_limited(solver, part, &input, &limits.limits())
+                            });
+                            let answer = answer?;
+                            cache.insert(solver, part, &input, &answer)?;
+                            (answer, counters)
+                        }
+                    };
+                    println!("Day {day} part {part}: {answer}");
+                    if metrics && counters.is_empty() {
+                        println!("  work: nothing reported");
+                    } else if metrics {
+                        println!("  work: {}", common::metrics::render(&counters));
+                    }
+                }
+            }
+        }
+        Command::Lint { day, input, root } => {
+            let path = input_path(&settings, input, &root, day)?;
+            let input = std::fs::read_to_string(&path)?;
+            let problems = solvers::select(day, &plugins)?.lint(&input);
+            for problem in problems.iter() {
+                println!("{}: {problem}", path.display());
+            }
+            if !problems.is_empty() {
+                return Err(anyhow!(
+                    "Found {} problems in {}",
+                    problems.len(),
+                    path.display()
+                ));
+            }
+            println!("{}: no problems found", path.display());
+        }
+        Command::Batch { day, dir, limits } => {
+            let results =
+                batch::run_batch(solvers::select(day, &plugins)?, &dir, &limits.limits())?;
+            print!("{}", batch::render_table(&results));
+        }
+        Command::Debug {
+            day,
+            part,
+            input,
+            root,
+        } => {
+            let path = input_path(&settings, input, &root, day)?;
+            let input = std::fs::read_to_string(&path)?;
+            let recording = debugger::record(day, part, &input)?;
+            debugger::run(&recording)?;
+        }
+        Command::Plugins => {
+            if cli.plugins.is_none() {
+                return Err(anyhow!("No plugin directory given, use --plugins"));
+            }
+            for plugin in plugins.loaded.iter() {
+                println!(
+                    "day {}: {} (version {})",
+                    plugin.day(),
+                    plugin.path().display(),
+                    plugin.version()
+                );
+            }
+        }
+        Command::Serve { port } => server::serve(port, &plugins)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! A tiny stand-in for the puzzle server so the clients can be tested offline
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+};
+
+use tiny_http::{Header, Response, Server};
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub cookie: Option<String>,
+    pub body: String,
+}
+
+type Routes = HashMap<(String, String), (u16, String)>;
+
+pub struct MockServer {
+    server: Arc<Server>,
+    port: u16,
+    routes: Arc<Mutex<Routes>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    pub fn start() -> Self {
+        let server = Arc::new(Server::http("127.0.0.1:0").expect("Failed to start mock server"));
+        let port = server
+            .server_addr()
+            .to_ip()
+            .expect("mock server is not listening on an ip address")
+            .port();
+        let routes: Arc<Mutex<Routes>> = Default::default();
+        let requests: Arc<Mutex<Vec<RecordedRequest>>> = Default::default();
+        let thread = {
+            let server = server.clone();
+            let routes = routes.clone();
+            let requests = requests.clone();
+            std::thread::spawn(move || {
+                for mut request in server.incoming_requests() {
+                    let mut body = String::new();
+                    let _ = request.as_reader().read_to_string(&mut body);
+                    let method = request.method().to_string();
+                    let path = request.url().to_string();
+                    let cookie = request
+                        .headers()
+                        .iter()
+                        .find(|h| h.field.equiv("Cookie"))
+                        .map(|h| h.value.to_string());
+                    requests.lock().unwrap().push(RecordedRequest {
+                        method: method.clone(),
+                        path: path.clone(),
+                        cookie,
+                        body,
+                    });
+                    let (status, body) = routes
+                        .lock()
+                        .unwrap()
+                        .get(&(method, path))
+                        .cloned()
+                        .unwrap_or((404, "Not Found".to_string()));
+                    let response = Response::from_string(body)
+                        .with_status_code(status)
+                        .with_header(
+                            Header::from_bytes("Content-Type", "text/plain").expect("bad header"),
+                        );
+                    let _ = request.respond(response);
+                }
+            })
+        };
+        Self {
+            server,
+            port,
+            routes,
+            requests,
+            thread: Some(thread),
+        }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    pub fn respond(&self, method: &str, path: &str, status: u16, body: &str) {
+        self.routes.lock().unwrap().insert(
+            (method.to_string(), path.to_string()),
+            (status, body.to_string()),
+        );
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Loads day solvers built as plugins (see [`common::plugin`]) so a day's
+//! implementation can be swapped without rebuilding the runner
+
+use std::{
+    ffi::c_void,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+use common::plugin::{
+    ABI_VERSION, DECLARATION_SYMBOL, DeclarationFn, PluginDecl, RawString, STATUS_OK,
+};
+use libloading::Library;
+use tracing::{debug, info, instrument, warn};
+
+use crate::solvers::Solve;
+
+pub struct Plugin {
+    path: PathBuf,
+    version: String,
+    decl: *const PluginDecl,
+    // must be dropped after everything that points into the library
+    _library: Option<Library>,
+}
+
+impl std::fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Plugin")
+            .field("path", &self.path)
+            .field("day", &self.day())
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+impl Plugin {
+    #[instrument(level = "debug")]
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        // SAFETY: loading a library runs its initializers, so we have to trust
+        // the contents of the plugin directory
+        let library = unsafe { Library::new(path) }
+            .with_context(|| format!("Failed to load {}", path.display()))?;
+        let decl = unsafe {
+            let declaration = library
+                .get::<DeclarationFn>(DECLARATION_SYMBOL)
+                .with_context(|| format!("{} is not an aoc plugin", path.display()))?;
+            declaration()
+        };
+        Self::from_decl(decl, path, Some(library))
+    }
+
+    pub(crate) fn from_decl(
+        decl: *const PluginDecl,
+        path: &Path,
+        library: Option<Library>,
+    ) -> anyhow::Result<Self> {
+        if decl.is_null() {
+            return Err(anyhow!("{} returned no declaration", path.display()));
+        }
+        // only the version is guaranteed to be at the same place in every
+        // version of the declaration
+        let abi_version = unsafe { (*decl).abi_version };
+        if abi_version != ABI_VERSION {
+            return Err(anyhow!(
+                "{} was built for plugin ABI version {abi_version} but this runner supports version {ABI_VERSION}; rebuild the plugin",
+                path.display()
+            ));
+        }
+        let version = unsafe { (*decl).version() };
+        Ok(Self {
+            path: path.to_path_buf(),
+            version,
+            decl,
+            _library: library,
+        })
+    }
+
+    fn decl(&self) -> &PluginDecl {
+        // SAFETY: checked for null and ABI compatibility when the plugin was
+        // loaded, and the library is kept alive as long as self
+        unsafe { &*self.decl }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Copies a string returned by the plugin and gives it back to the plugin
+    /// to free
+    fn take_string(&self, s: RawString) -> String {
+        unsafe {
+            let text = s.to_string_lossy();
+            (self.decl().free_string)(s);
+            text
+        }
+    }
+}
+
+impl Solve for Plugin {
+    fn day(&self) -> u32 {
+        self.decl().day
+    }
+
+    #[instrument(level = "debug", skip(self, input))]
+    fn solve(&self, part: u32, input: &str) -> anyhow::Result<String> {
+        let decl = self.decl();
+        let solve = match part {
+            1 => decl.part1,
+            2 => decl.part2,
+            _ => return Err(anyhow!("Invalid part {part}")),
+        };
+        let mut parsed: *mut c_void = std::ptr::null_mut();
+        let mut error = RawString::empty();
+        let status = unsafe { (decl.parse)(input.as_ptr(), input.len(), &mut parsed, &mut error) };
+        if status != STATUS_OK {
+            return Err(anyhow!("{}", self.take_string(error)));
+        }
+        let mut answer = RawString::empty();
+        let status = unsafe { solve(parsed, &mut answer) };
+        unsafe { (decl.free_parsed)(parsed) };
+        let answer = self.take_string(answer);
+        if status != STATUS_OK {
+            return Err(anyhow!("{answer}"));
+        }
+        Ok(answer)
+    }
+
+    /// The plugin's version and when its library was last written, since
+    /// plugins don't carry a build id of their own
+    fn build_id(&self) -> String {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |time| time.as_nanos());
+        format!("{}@{modified}", self.version)
+    }
+}
+
+/// The plugins found in a directory, and the files that couldn't be loaded
+#[derive(Debug, Default)]
+pub struct Plugins {
+    pub loaded: Vec<Plugin>,
+    pub failed: Vec<(PathBuf, anyhow::Error)>,
+}
+
+impl Plugins {
+    /// Loads every dynamic library in `dir`. Only the first plugin for each
+    /// day is kept
+    #[instrument(level = "debug")]
+    pub fn discover(dir: &Path) -> anyhow::Result<Self> {
+        let mut paths = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read plugin directory {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+        let mut plugins = Self::default();
+        for path in paths {
+            match Plugin::load(&path) {
+                Ok(plugin) => {
+                    if let Some(existing) = plugins.get(plugin.day()) {
+                        let err = anyhow!(
+                            "a plugin for day {} was already loaded from {}",
+                            plugin.day(),
+                            existing.path().display()
+                        );
+                        plugins.failed.push((path, err));
+                    } else {
+                        info!(?plugin, "loaded plugin");
+                        plugins.loaded.push(plugin);
+                    }
+                }
+                Err(e) => {
+                    warn!(?path, "failed to load plugin: {e:#}");
+                    plugins.failed.push((path, e));
+                }
+            }
+        }
+        debug!(?plugins);
+        Ok(plugins)
+    }
+
+    pub fn get(&self, day: u32) -> Option<&Plugin> {
+        self.loaded.iter().find(|plugin| plugin.day() == day)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::plugin::Solver;
+    use test_log::test;
+
+    struct Counter;
+
+    impl Solver for Counter {
+        type Parsed = Vec<String>;
+        const DAY: u32 = 3;
+
+        fn parse(input: &str) -> anyhow::Result<Vec<String>> {
+            if input.is_empty() {
+                return Err(anyhow!("empty input"));
+            }
+            Ok(input.lines().map(String::from).collect())
+        }
+
+        fn part1(lines: &Vec<String>) -> anyhow::Result<String> {
+            Ok(lines.len().to_string())
+        }
+
+        fn part2(_lines: &Vec<String>) -> anyhow::Result<String> {
+            Err(anyhow!("not yet"))
+        }
+    }
+
+    const DECL: PluginDecl = PluginDecl::new::<Counter>("0.2.0");
+
+    #[test]
+    fn test_solve() {
+        let plugin = Plugin::from_decl(&DECL, Path::new("libcounter.so"), None)
+            .expect("Failed to load plugin");
+        assert_eq!(3, plugin.day());
+        assert_eq!("0.2.0", plugin.version());
+        assert_eq!("2", plugin.solve(1, "a\nb\n").expect("part 1 failed"));
+        let err = plugin.solve(2, "a\nb\n").expect_err("part 2 should fail");
+        assert_eq!("not yet", err.to_string());
+        let err = plugin.solve(1, "").expect_err("parse should fail");
+        assert_eq!("empty input", err.to_string());
+        assert!(plugin.solve(3, "a").is_err());
+    }
+
+    #[test]
+    fn test_version_mismatch() {
+        let mut decl = PluginDecl::new::<Counter>("0.2.0");
+        decl.abi_version = ABI_VERSION + 1;
+        let err = Plugin::from_decl(&decl, Path::new("libcounter.so"), None)
+            .expect_err("should reject plugin");
+        assert!(err.to_string().contains("rebuild the plugin"));
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let bogus = dir
+            .path()
+            .join(format!("libbogus.{}", std::env::consts::DLL_EXTENSION));
+        std::fs::write(&bogus, "not a library").unwrap();
+        std::fs::write(dir.path().join("README"), "ignored").unwrap();
+        let plugins = Plugins::discover(dir.path()).expect("Failed to discover");
+        assert!(plugins.loaded.is_empty());
+        assert_eq!(1, plugins.failed.len());
+        assert_eq!(bogus, plugins.failed[0].0);
+    }
+}
@@ -0,0 +1,277 @@
+//! A local HTTP service that solves puzzle inputs, so that other tools can use
+//! the solvers without running a separate binary for each day.
+//!
+//! The only route is `POST /day/{n}/part/{p}`, with the puzzle input as the
+//! body. Answers and errors are both returned as JSON
+
+use anyhow::anyhow;
+use common::{lint::Problem, metrics::Counters};
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+use tracing::{info, instrument, warn};
+
+use crate::{batch, plugins::Plugins, solvers};
+
+pub const DEFAULT_PORT: u16 = 8025;
+
+/// The body of a successful response
+#[derive(Debug, Serialize)]
+pub struct Answer {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub elapsed_ms: f64,
+    /// the work the solver reported doing
+    pub counters: Counters,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    NotFound,
+    MethodNotAllowed,
+    UnknownDay,
+    InvalidPart,
+    /// the body isn't utf-8
+    InvalidBody,
+    /// the input failed the day's lint
+    InvalidInput,
+    /// the solver returned an error or panicked
+    SolveFailed,
+}
+
+impl ErrorKind {
+    fn status(self) -> u16 {
+        match self {
+            Self::NotFound | Self::UnknownDay | Self::InvalidPart => 404,
+            Self::MethodNotAllowed => 405,
+            Self::InvalidBody => 400,
+            Self::InvalidInput | Self::SolveFailed => 422,
+        }
+    }
+}
+
+/// The body of a failed response, nested under `error`
+#[derive(Debug, Serialize)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// everything the lint found, for `invalid_input`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub problems: Vec<Problem>,
+}
+
+impl Error {
+    fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            problems: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: Error,
+}
+
+/// A status code and JSON body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Reply {
+    fn json(status: u16, body: &impl Serialize) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string(body).expect("responses are always serializable"),
+        }
+    }
+}
+
+impl From<Error> for Reply {
+    fn from(error: Error) -> Self {
+        Self::json(error.kind.status(), &ErrorResponse { error })
+    }
+}
+
+/// Parses `/day/{n}/part/{p}`, ignoring any query string
+fn route(url: &str) -> Option<(&str, &str)> {
+    let path = url.split('?').next().unwrap_or(url);
+    match path.trim_matches('/').split('/').collect::<Vec<_>>()[..] {
+        ["day", day, "part", part] => Some((day, part)),
+        _ => None,
+    }
+}
+
+/// Answers a single request. `body` is `None` if it couldn't be read as text
+pub fn handle(method: &Method, url: &str, body: Option<&str>, plugins: &Plugins) -> Reply {
+    let Some((day, part)) = route(url) else {
+        return Error::new(ErrorKind::NotFound, format!("No route for {url}")).into();
+    };
+    if *method != Method::Post {
+        return Error::new(
+            ErrorKind::MethodNotAllowed,
+            format!("{method} is not allowed, use POST"),
+        )
+        .into();
+    }
+    let Some(solver) = day
+        .parse()
+        .ok()
+        .and_then(|day| solvers::select(day, plugins).ok())
+    else {
+        return Error::new(ErrorKind::UnknownDay, format!("No solver for day {day}")).into();
+    };
+    let part = match part.parse() {
+        Ok(part @ 1..=2) => part,
+        _ => return Error::new(ErrorKind::InvalidPart, format!("Invalid part {part}")).into(),
+    };
+    let Some(input) = body else {
+        return Error::new(ErrorKind::InvalidBody, "The input is not utf-8").into();
+    };
+    let problems = solver.lint(input);
+    if let Err(e) = common::lint::check(problems.clone()) {
+        return Error {
+            kind: ErrorKind::InvalidInput,
+            message: e.to_string(),
+            problems,
+        }
+        .into();
+    }
+    let outcome = batch::run_part(|input| solver.solve(part, input), input);
+    match outcome.answer {
+        Ok(answer) => Reply::json(
+            200,
+            &Answer {
+                day: solver.day(),
+                part,
+                answer,
+                elapsed_ms: outcome.elapsed.as_secs_f64() * 1000.0,
+                counters: outcome.counters,
+            },
+        ),
+        Err(message) => Error::new(ErrorKind::SolveFailed, message).into(),
+    }
+}
+
+fn respond(mut request: Request, plugins: &Plugins) {
+    let mut body = String::new();
+    let body = request
+        .as_reader()
+        .read_to_string(&mut body)
+        .ok()
+        .map(|_| body.as_str());
+    let reply = handle(request.method(), request.url(), body, plugins);
+    info!(method = %request.method(), url = request.url(), status = reply.status, "handled");
+    let response = Response::from_string(reply.body)
+        .with_status_code(reply.status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").expect("bad header"));
+    if let Err(e) = request.respond(response) {
+        warn!(%e, "failed to send response");
+    }
+}
+
+/// Handles requests one at a time until the server is unblocked
+pub fn run(server: &Server, plugins: &Plugins) {
+    for request in server.incoming_requests() {
+        respond(request, plugins);
+    }
+}
+
+/// Listens on `port`, on localhost only, until the process is stopped
+#[instrument(level = "debug", skip(plugins))]
+pub fn serve(port: u16, plugins: &Plugins) -> anyhow::Result<()> {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|e| anyhow!("Failed to listen on port {port}: {e}"))?;
+    println!("Listening on http://{}", server.server_addr());
+    run(&server, plugins);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    const DAY5: &str = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+
+    fn error(reply: &Reply) -> serde_json::Value {
+        let body: serde_json::Value = serde_json::from_str(&reply.body).expect("Invalid json");
+        body["error"].clone()
+    }
+
+    #[test]
+    fn test_handle() {
+        let plugins = Plugins::default();
+        let reply = handle(&Method::Post, "/day/5/part/2", Some(DAY5), &plugins);
+        assert_eq!(200, reply.status);
+        let body: serde_json::Value = serde_json::from_str(&reply.body).expect("Invalid json");
+        assert_eq!("14", body["answer"]);
+        assert_eq!(5, body["day"]);
+        assert!(body["elapsed_ms"].as_f64().is_some());
+        assert!(body["counters"]["ranges merged"].as_u64().is_some());
+
+        let reply = handle(&Method::Post, "/day/5/part/1?x=1", Some(DAY5), &plugins);
+        assert_eq!(200, reply.status);
+
+        let reply = handle(&Method::Get, "/day/5/part/1", None, &plugins);
+        assert_eq!(
+            (405, "method_not_allowed".into()),
+            (reply.status, error(&reply)["kind"].clone())
+        );
+        let reply = handle(&Method::Post, "/days", Some(""), &plugins);
+        assert_eq!("not_found", error(&reply)["kind"]);
+        let reply = handle(&Method::Post, "/day/9/part/1", Some(""), &plugins);
+        assert_eq!("unknown_day", error(&reply)["kind"]);
+        let reply = handle(&Method::Post, "/day/5/part/3", Some(""), &plugins);
+        assert_eq!("invalid_part", error(&reply)["kind"]);
+        let reply = handle(&Method::Post, "/day/5/part/1", None, &plugins);
+        assert_eq!(
+            (400, "invalid_body".into()),
+            (reply.status, error(&reply)["kind"].clone())
+        );
+    }
+
+    #[test]
+    fn test_handle_invalid_input() {
+        let plugins = Plugins::default();
+        let reply = handle(
+            &Method::Post,
+            "/day/5/part/1",
+            Some("3-5\n9-1\n\nx\n"),
+            &plugins,
+        );
+        assert_eq!(422, reply.status);
+        let error = error(&reply);
+        assert_eq!("invalid_input", error["kind"]);
+        let problems = error["problems"].as_array().expect("No problems");
+        assert_eq!(2, problems.len());
+        assert_eq!(2, problems[0]["line"]);
+    }
+
+    #[test]
+    fn test_serve() {
+        let server = Server::http("127.0.0.1:0").expect("Failed to start server");
+        let url = format!("http://{}/day/5/part/2", server.server_addr());
+        let plugins = Plugins::default();
+        // plugins can't be shared between threads, so the client is the one
+        // that runs elsewhere
+        let body = std::thread::scope(|scope| {
+            let client = scope.spawn(|| {
+                let body = ureq::post(&url)
+                    .send(DAY5)
+                    .and_then(|mut response| response.body_mut().read_to_string());
+                server.unblock();
+                body
+            });
+            run(&server, &plugins);
+            client.join().expect("client panicked")
+        })
+        .expect("Request failed");
+        assert!(body.contains(r#""answer":"14""#), "{body}");
+    }
+}
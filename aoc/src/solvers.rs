@@ -0,0 +1,237 @@
+use anyhow::anyhow;
+
+use common::{
+    lint::Problem,
+    progress::{self, Limits},
+};
+
+use crate::plugins::Plugins;
+
+pub type SolveFn = fn(&str) -> anyhow::Result<String>;
+pub type LintFn = fn(&str) -> Vec<Problem>;
+
+/// The entry points for a single day. Every solver takes the raw puzzle input
+/// and returns the answer in the form it would be submitted
+pub struct Solver {
+    pub day: u32,
+    /// changes whenever the day's crate is rebuilt
+    pub build: &'static str,
+    /// checks the structure of the input, run before either part
+    pub lint: LintFn,
+    pub part1: SolveFn,
+    pub part2: SolveFn,
+}
+
+pub const SOLVERS: &[Solver] = &[
+    Solver {
+        day: 1,
+        build: day1::BUILD_ID,
+        lint: day1::lint,
+        part1: |input| Ok(day1::part1(input)?),
+        part2: |input| Ok(day1::part2(input)?),
+    },
+    Solver {
+        day: 2,
+        build: day2::BUILD_ID,
+        lint: day2::lint,
+        part1: |input| Ok(day2::part1(&day2::parse(input)?).to_string()),
+        part2: |input| Ok(day2::part2(&day2::parse(input)?).to_string()),
+    },
+    Solver {
+        day: 3,
+        build: day3::BUILD_ID,
+        lint: day3::lint,
+        part1: |input| Ok(day3::part1(&day3::parse(input)?).to_string()),
+        part2: |input| Ok(day3::part2(&day3::parse(input)?)?.to_string()),
+    },
+    Solver {
+        day: 4,
+        build: day4::BUILD_ID,
+        lint: day4::lint,
+        part1: |input| {
+            let map = day4::Map::parse(input)?;
+            Ok(map.find_accessible_locations().len().to_string())
+        },
+        part2: |input| {
+            let mut map = day4::Map::parse(input)?;
+            Ok(map.part2().to_string())
+        },
+    },
+    Solver {
+        day: 5,
+        build: day5::BUILD_ID,
+        lint: day5::lint,
+        part1: |input| Ok(day5::part1(input)?.to_string()),
+        part2: |input| Ok(day5::part2(input)?.to_string()),
+    },
+    Solver {
+        day: 6,
+        build: day6::BUILD_ID,
+        lint: day6::lint,
+        part1: |input| Ok(day6::part1(input)?),
+        part2: |input| Ok(day6::part2(input)?),
+    },
+    Solver {
+        day: 7,
+        build: day7::BUILD_ID,
+        lint: day7::lint,
+        part1: |input| Ok(day7::part1(input)?),
+        part2: |input| Ok(day7::part2(input)?),
+    },
+];
+
+pub fn solver(day: u32) -> anyhow::Result<&'static Solver> {
+    SOLVERS
+        .iter()
+        .find(|solver| solver.day == day)
+        .ok_or_else(|| anyhow!("No solver for day {day}"))
+}
+
+impl Solver {
+    pub fn part(&self, part: u32) -> anyhow::Result<SolveFn> {
+        match part {
+            1 => Ok(self.part1),
+            2 => Ok(self.part2),
+            _ => Err(anyhow!("Invalid part {part}")),
+        }
+    }
+}
+
+/// Anything that can solve a day's puzzle, whether built in or loaded from a
+/// plugin
+pub trait Solve {
+    fn day(&self) -> u32;
+    fn solve(&self, part: u32, input: &str) -> anyhow::Result<String>;
+
+    /// Solves input that [`Solve::lint`] has already passed, without linting
+    /// it again
+    fn solve_linted(&self, part: u32, input: &str) -> anyhow::Result<String> {
+        self.solve(part, input)
+    }
+
+    /// Identifies the build of the solver, so that answers cached from
+    /// another build aren't trusted
+    fn build_id(&self) -> String;
+
+    /// Every structural problem with the input. Solvers that can't check
+    /// their input report none
+    fn lint(&self, _input: &str) -> Vec<Problem> {
+        Vec::new()
+    }
+}
+
+impl Solve for Solver {
+    fn day(&self) -> u32 {
+        self.day
+    }
+
+    fn solve(&self, part: u32, input: &str) -> anyhow::Result<String> {
+        let solve = self.part(part)?;
+        common::lint::check((self.lint)(input))?;
+        solve(input)
+    }
+
+    fn solve_linted(&self, part: u32, input: &str) -> anyhow::Result<String> {
+        self.part(part)?(input)
+    }
+
+    fn build_id(&self) -> String {
+        self.build.to_string()
+    }
+
+    fn lint(&self, input: &str) -> Vec<Problem> {
+        (self.lint)(input)
+    }
+}
+
+/// Finds the solver for a day, preferring a plugin over the built-in solver
+pub fn select(day: u32, plugins: &Plugins) -> anyhow::Result<&dyn Solve> {
+    match plugins.get(day) {
+        Some(plugin) => Ok(plugin),
+        None => Ok(solver(day)?),
+    }
+}
+
+pub fn solve(day: u32, part: u32, input: &str) -> anyhow::Result<String> {
+    solver(day)?.solve(part, input)
+}
+
+/// Lints and then solves one part, giving each phase its own time limit
+pub fn solve_limited(
+    solver: &dyn Solve,
+    part: u32,
+    input: &str,
+    limits: &Limits,
+) -> anyhow::Result<String> {
+    let problems = progress::run_phase("lint", limits, || solver.lint(input))?;
+    common::lint::check(problems)?;
+    progress::run_phase(format!("part {part}"), limits, || {
+        solver.solve_linted(part, input)
+    })?
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(
+            "3",
+            solve(1, 1, "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n").unwrap()
+        );
+        assert_eq!(
+            "14",
+            solve(5, 2, "3-5\n10-14\n16-20\n12-18\n\n1\n").unwrap()
+        );
+        let err = solve(5, 1, "3-5\n9-1\n\nx\n").expect_err("should fail lint");
+        assert!(err.to_string().starts_with("Input has 2 problems"));
+        assert!(solve(8, 1, "").is_err());
+        assert!(solve(1, 3, "").is_err());
+    }
+
+    #[test]
+    fn test_solve_limited() {
+        let input = "3-5\n10-14\n16-20\n12-18\n\n1\n";
+        let solver = solver(5).unwrap();
+        assert_eq!(
+            "14",
+            solve_limited(solver, 2, input, &Limits::default()).unwrap()
+        );
+        let limits = Limits {
+            timeout: Some(std::time::Duration::ZERO),
+            progress: None,
+        };
+        let err = solve_limited(solver, 2, input, &limits).expect_err("should time out");
+        assert!(err.to_string().contains("timed out"), "{err}");
+    }
+
+    #[test]
+    fn test_solve_limited_lints_once() {
+        static LINTS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let solver = Solver {
+            day: 0,
+            build: "test",
+            lint: |_| {
+                LINTS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Vec::new()
+            },
+            part1: |input| Ok(input.len().to_string()),
+            part2: |input| Ok(input.to_uppercase()),
+        };
+        assert_eq!(
+            "ABC",
+            solve_limited(&solver, 2, "abc", &Limits::default()).unwrap()
+        );
+        assert_eq!(1, LINTS.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_select_builtin() {
+        let plugins = Plugins::default();
+        let solver = select(6, &plugins).expect("No solver");
+        assert_eq!(6, solver.day());
+        assert!(select(0, &plugins).is_err());
+    }
+}
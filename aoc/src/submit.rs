@@ -0,0 +1,320 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+/// The server's judgement of a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// wrong, but the server didn't say in which direction
+    Incorrect,
+    /// the answer wasn't judged because we submitted too recently
+    RateLimited {
+        wait: Option<String>,
+    },
+    /// the part has already been solved, so the answer wasn't judged
+    AlreadySolved,
+    Unknown(String),
+}
+
+impl Verdict {
+    #[instrument(ret, level = "debug", skip(body))]
+    pub fn from_response(body: &str) -> Self {
+        if body.contains("That's the right answer") {
+            Self::Correct
+        } else if body.contains("your answer is too high") {
+            Self::TooHigh
+        } else if body.contains("your answer is too low") {
+            Self::TooLow
+        } else if body.contains("That's not the right answer") {
+            Self::Incorrect
+        } else if body.contains("You gave an answer too recently") {
+            let wait = body
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_string());
+            Self::RateLimited { wait }
+        } else if body.contains("You don't seem to be solving the right level") {
+            Self::AlreadySolved
+        } else {
+            Self::Unknown(body.to_string())
+        }
+    }
+
+    /// whether the server actually judged the answer
+    pub fn is_judged(&self) -> bool {
+        matches!(
+            self,
+            Self::Correct | Self::TooHigh | Self::TooLow | Self::Incorrect
+        )
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::TooHigh => write!(f, "wrong (too high)"),
+            Self::TooLow => write!(f, "wrong (too low)"),
+            Self::Incorrect => write!(f, "wrong"),
+            Self::RateLimited { wait: Some(wait) } => write!(f, "rate-limited ({wait} left)"),
+            Self::RateLimited { wait: None } => write!(f, "rate-limited"),
+            Self::AlreadySolved => write!(f, "already solved"),
+            Self::Unknown(_) => write!(f, "unrecognized response"),
+        }
+    }
+}
+
+/// Why a guess was refused without being sent to the server
+#[derive(Debug, PartialEq, Eq)]
+pub enum Refusal {
+    AlreadyGuessed(Verdict),
+    AlreadySolved { answer: String },
+    NotAboveLowerBound { bound: i128 },
+    NotBelowUpperBound { bound: i128 },
+}
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AlreadyGuessed(verdict) => {
+                write!(f, "this answer was already submitted and was {verdict}")
+            }
+            Self::AlreadySolved { answer } => {
+                write!(f, "this part was already solved with answer {answer}")
+            }
+            Self::NotAboveLowerBound { bound } => {
+                write!(f, "the answer must be higher than {bound}")
+            }
+            Self::NotBelowUpperBound { bound } => {
+                write!(f, "the answer must be lower than {bound}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Guess {
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// Every answer submitted for one part of one day, stored as JSON
+#[derive(Debug)]
+pub struct GuessLog {
+    path: PathBuf,
+    pub guesses: Vec<Guess>,
+}
+
+impl GuessLog {
+    pub fn path(dir: &Path, year: u32, day: u32, part: u32) -> PathBuf {
+        dir.join(year.to_string())
+            .join(format!("day{day}.part{part}.guesses.json"))
+    }
+
+    #[instrument(level = "debug")]
+    pub fn load(dir: &Path, year: u32, day: u32, part: u32) -> anyhow::Result<Self> {
+        let path = Self::path(dir, year, day, part);
+        let guesses = if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read guesses from {}", path.display()))?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse guesses from {}", path.display()))?
+        } else {
+            Vec::default()
+        };
+        debug!(?guesses);
+        Ok(Self { path, guesses })
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.guesses)?)
+            .with_context(|| format!("Failed to write guesses to {}", self.path.display()))
+    }
+
+    pub fn record(&mut self, answer: &str, verdict: Verdict) {
+        self.guesses.push(Guess {
+            answer: answer.to_string(),
+            verdict,
+        });
+    }
+
+    /// the highest answer known to be too low and the lowest answer known to
+    /// be too high
+    pub fn bounds(&self) -> (Option<i128>, Option<i128>) {
+        let numeric = |verdict: Verdict| {
+            self.guesses
+                .iter()
+                .filter(move |guess| guess.verdict == verdict)
+                .filter_map(|guess| guess.answer.trim().parse::<i128>().ok())
+        };
+        (
+            numeric(Verdict::TooLow).max(),
+            numeric(Verdict::TooHigh).min(),
+        )
+    }
+
+    /// Checks a candidate answer against everything we've learned so far
+    pub fn check(&self, answer: &str) -> Result<(), Refusal> {
+        if let Some(correct) = self
+            .guesses
+            .iter()
+            .find(|guess| guess.verdict == Verdict::Correct)
+        {
+            return Err(Refusal::AlreadySolved {
+                answer: correct.answer.clone(),
+            });
+        }
+        if let Some(guess) = self
+            .guesses
+            .iter()
+            .find(|guess| guess.verdict.is_judged() && guess.answer == answer)
+        {
+            return Err(Refusal::AlreadyGuessed(guess.verdict.clone()));
+        }
+        if let Ok(value) = answer.trim().parse::<i128>() {
+            let (lower, upper) = self.bounds();
+            if let Some(bound) = lower.filter(|&bound| value <= bound) {
+                return Err(Refusal::NotAboveLowerBound { bound });
+            }
+            if let Some(bound) = upper.filter(|&bound| value >= bound) {
+                return Err(Refusal::NotBelowUpperBound { bound });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{client::Client, mock::MockServer};
+    use test_log::test;
+
+    #[test]
+    fn test_parse_verdict() {
+        assert_eq!(
+            Verdict::Correct,
+            Verdict::from_response(
+                "<article><p>That's the right answer!  You are one gold star closer.</p></article>"
+            )
+        );
+        assert_eq!(
+            Verdict::TooHigh,
+            Verdict::from_response(
+                "<article><p>That's not the right answer; your answer is too high.</p></article>"
+            )
+        );
+        assert_eq!(
+            Verdict::TooLow,
+            Verdict::from_response(
+                "<article><p>That's not the right answer; your answer is too low.</p></article>"
+            )
+        );
+        assert_eq!(
+            Verdict::Incorrect,
+            Verdict::from_response(
+                "<article><p>That's not the right answer.  If you're stuck...</p></article>"
+            )
+        );
+        assert_eq!(
+            Verdict::RateLimited {
+                wait: Some("47s".to_string())
+            },
+            Verdict::from_response(
+                "<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 47s left to wait.</p></article>"
+            )
+        );
+        assert_eq!(
+            Verdict::AlreadySolved,
+            Verdict::from_response(
+                "<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>"
+            )
+        );
+        assert!(matches!(
+            Verdict::from_response("<html>500</html>"),
+            Verdict::Unknown(_)
+        ));
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = tempfile::tempdir().expect("Failed to create tempdir");
+        let mut log = GuessLog::load(dir.path(), 2025, 1, 1).expect("Failed to load");
+        assert!(log.guesses.is_empty());
+        assert_eq!(Ok(()), log.check("500"));
+
+        log.record("500", Verdict::TooHigh);
+        log.record("100", Verdict::TooLow);
+        log.record("300", Verdict::RateLimited { wait: None });
+        log.record("250", Verdict::Incorrect);
+        assert_eq!((Some(100), Some(500)), log.bounds());
+
+        assert_eq!(
+            Err(Refusal::AlreadyGuessed(Verdict::TooHigh)),
+            log.check("500")
+        );
+        assert_eq!(
+            Err(Refusal::AlreadyGuessed(Verdict::Incorrect)),
+            log.check("250")
+        );
+        assert_eq!(
+            Err(Refusal::NotBelowUpperBound { bound: 500 }),
+            log.check("600")
+        );
+        assert_eq!(
+            Err(Refusal::NotAboveLowerBound { bound: 100 }),
+            log.check("99")
+        );
+        // rate limited answers were never judged
+        assert_eq!(Ok(()), log.check("300"));
+        assert_eq!(Ok(()), log.check("not a number"));
+
+        log.save().expect("Failed to save");
+        let mut log = GuessLog::load(dir.path(), 2025, 1, 1).expect("Failed to load");
+        assert_eq!(4, log.guesses.len());
+        log.record("300", Verdict::Correct);
+        assert_eq!(
+            Err(Refusal::AlreadySolved {
+                answer: "300".to_string()
+            }),
+            log.check("301")
+        );
+
+        // other parts are tracked separately
+        let log = GuessLog::load(dir.path(), 2025, 1, 2).expect("Failed to load");
+        assert_eq!(Ok(()), log.check("500"));
+    }
+
+    #[test]
+    fn test_submit() {
+        let server = MockServer::start();
+        server.respond(
+            "POST",
+            "/2025/day/1/answer",
+            200,
+            "<main><article><p>That's not the right answer; your answer is too low.</p></article></main>",
+        );
+        let client = Client::new(&server.url(), "secret");
+        let verdict = client
+            .submit_answer(2025, 1, 2, "1234")
+            .expect("Failed to submit");
+        assert_eq!(Verdict::TooLow, verdict);
+
+        let requests = server.requests();
+        assert_eq!(1, requests.len());
+        assert_eq!("POST", requests[0].method);
+        assert_eq!("level=2&answer=1234", requests[0].body);
+        assert_eq!(Some("session=secret".to_string()), requests[0].cookie);
+    }
+}
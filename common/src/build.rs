@@ -0,0 +1,24 @@
+//! Build ids, which tell the runner when a day's cached answers are stale.
+//!
+//! Every day's build script calls [`emit_build_id`], and its library declares
+//! the id with [`declare_build_id!`](crate::declare_build_id)
+
+/// Gives the crate being built a `BUILD_ID` environment variable. Cargo
+/// reruns build scripts whenever a file in their crate changes, so every
+/// rebuild gets a new id
+#[cfg(feature = "std")]
+pub fn emit_build_id() {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock is before 1970");
+    println!("cargo:rustc-env=BUILD_ID={}", now.as_nanos());
+}
+
+/// Declares `BUILD_ID`, set by [`emit_build_id`] in the crate's build script
+#[macro_export]
+macro_rules! declare_build_id {
+    () => {
+        /// Different for every build of this crate
+        pub const BUILD_ID: &str = env!("BUILD_ID");
+    };
+}
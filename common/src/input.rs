@@ -0,0 +1,266 @@
+//! Puzzle input that can be consumed line by line, either from a memory-mapped
+//! file or from any [`BufRead`], so that line-oriented days don't have to hold
+//! the whole input in a `String`
+
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufRead},
+    path::Path,
+};
+
+use anyhow::{Context, anyhow};
+use memmap2::Mmap;
+
+use crate::lint::LineLint;
+
+pub enum Input {
+    Mapped(Mmap),
+    Reader(Box<dyn BufRead>),
+}
+
+impl std::fmt::Debug for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mapped(map) => f.debug_tuple("Mapped").field(&map.len()).finish(),
+            Self::Reader(_) => f.debug_tuple("Reader").finish(),
+        }
+    }
+}
+
+impl Input {
+    /// Memory-maps the file at `path`, or reads standard input if `path` is
+    /// `-`
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        if path == Path::new("-") {
+            return Ok(Self::from_reader(std::io::stdin().lock()));
+        }
+        let file =
+            File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        if file.metadata()?.len() == 0 {
+            // empty files can't be mapped on every platform
+            return Ok(Self::from_reader(std::io::empty()));
+        }
+        // SAFETY: the map is only read, but the file must not be truncated
+        // while we're solving
+        let map = unsafe { Mmap::map(&file) }
+            .with_context(|| format!("Failed to map {}", path.display()))?;
+        Ok(Self::Mapped(map))
+    }
+
+    /// Reads the input from a file or anything else that can be buffered
+    pub fn from_reader(reader: impl BufRead + 'static) -> Self {
+        Self::Reader(Box::new(reader))
+    }
+
+    /// The whole input, when it's mapped rather than streamed
+    pub fn as_str(&self) -> Option<anyhow::Result<&str>> {
+        match self {
+            Self::Mapped(map) => {
+                Some(std::str::from_utf8(map).map_err(|e| anyhow!("Input is not utf-8: {e}")))
+            }
+            Self::Reader(_) => None,
+        }
+    }
+
+    /// Hands the lines to `solve`, linting each one with `L` on its way past,
+    /// so that streamed input is checked as well as mapped input. Problems
+    /// the lint finds are reported instead of the solver's answer or error,
+    /// and if the solver fails part way the rest of the lines are linted too
+    pub fn solve_linted<L, T, E>(
+        &mut self,
+        solve: impl FnOnce(&mut Linted<'_, L>) -> Result<T, E>,
+    ) -> anyhow::Result<T>
+    where
+        L: LineLint,
+        E: Into<anyhow::Error>,
+    {
+        let mut linted = Linted {
+            lines: self.lines(),
+            lint: L::default(),
+            number: 0,
+        };
+        let solved = solve(&mut linted);
+        if solved.is_err() {
+            while let Some(Ok(_)) = linted.next() {}
+        }
+        crate::lint::check(linted.lint.finish())?;
+        solved.map_err(Into::into)
+    }
+
+    /// The lines of the input, without their line endings, like
+    /// [`str::lines`]. Lines that aren't utf-8 are `InvalidData` errors
+    pub fn lines(&mut self) -> Lines<'_> {
+        let source = match self {
+            Self::Mapped(map) => Source::Mapped(map),
+            Self::Reader(reader) => Source::Reader(reader.as_mut()),
+        };
+        Lines { source, number: 0 }
+    }
+}
+
+enum Source<'a> {
+    /// what is left of the map
+    Mapped(&'a [u8]),
+    Reader(&'a mut dyn BufRead),
+}
+
+pub struct Lines<'a> {
+    source: Source<'a>,
+    number: usize,
+}
+
+fn strip_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = io::Result<Cow<'a, str>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.number += 1;
+        let number = self.number;
+        let line: io::Result<Cow<'a, str>> = match &mut self.source {
+            Source::Mapped(rest) => {
+                if rest.is_empty() {
+                    return None;
+                }
+                let end = rest
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(rest.len(), |i| i + 1);
+                let (line, remaining) = rest.split_at(end);
+                *rest = remaining;
+                std::str::from_utf8(strip_line_ending(line))
+                    .map(Cow::Borrowed)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Source::Reader(reader) => {
+                let mut line = Vec::new();
+                match reader.read_until(b'\n', &mut line) {
+                    Ok(0) => return None,
+                    Ok(_) => {
+                        line.truncate(strip_line_ending(&line).len());
+                        String::from_utf8(line)
+                            .map(Cow::Owned)
+                            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        };
+        Some(
+            line.map_err(|e| {
+                io::Error::new(e.kind(), format!("Failed to read line {number}: {e}"))
+            }),
+        )
+    }
+}
+
+/// The lines of an [`Input`], linted as they are read. See
+/// [`Input::solve_linted`]
+pub struct Linted<'a, L> {
+    lines: Lines<'a>,
+    lint: L,
+    number: usize,
+}
+
+impl<'a, L: LineLint> Iterator for Linted<'a, L> {
+    type Item = io::Result<Cow<'a, str>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        self.number += 1;
+        if let Ok(line) = &line {
+            self.lint.line(self.number, line);
+        }
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::lint::Problem;
+
+    const TEXT: &str = "first\r\nsecond\n\nfourth";
+
+    #[test]
+    fn test_reader_lines() {
+        let mut input = Input::from_reader(Cursor::new(TEXT));
+        assert!(input.as_str().is_none());
+        let lines = input.lines().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(TEXT.lines().collect::<Vec<_>>(), lines);
+    }
+
+    #[test]
+    fn test_mapped_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input");
+        std::fs::write(&path, TEXT).unwrap();
+        let mut input = Input::open(&path).expect("Failed to open");
+        assert_eq!(TEXT, input.as_str().unwrap().unwrap());
+        let lines = input.lines().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(TEXT.lines().collect::<Vec<_>>(), lines);
+        assert!(matches!(lines[0], Cow::Borrowed(_)));
+
+        std::fs::write(&path, "").unwrap();
+        let mut input = Input::open(&path).expect("Failed to open");
+        assert_eq!(0, input.lines().count());
+        assert!(Input::open(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let mut input = Input::from_reader(Cursor::new(b"ok\n\xff\xfe\n".to_vec()));
+        let mut lines = input.lines();
+        assert_eq!("ok", lines.next().unwrap().unwrap());
+        let err = lines.next().unwrap().expect_err("should be invalid");
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    /// Complains about lines that aren't lowercase
+    #[derive(Default)]
+    struct Lowercase(Vec<Problem>);
+
+    impl LineLint for Lowercase {
+        fn line(&mut self, number: usize, line: &str) {
+            if line.chars().any(char::is_uppercase) {
+                self.0.push(Problem::new(number, "not lowercase"));
+            }
+        }
+
+        fn finish(self) -> Vec<Problem> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_solve_linted() {
+        let count = |lines: &mut Linted<'_, Lowercase>| -> io::Result<usize> {
+            lines.try_fold(0, |n, line| line.map(|_| n + 1))
+        };
+        let mut input = Input::from_reader(Cursor::new("a\nb\n"));
+        assert_eq!(2, input.solve_linted(count).unwrap());
+
+        let mut input = Input::from_reader(Cursor::new("One\ntwo\nThree"));
+        let err = input.solve_linted(count).unwrap_err();
+        assert!(err.to_string().contains("2 problems"), "{err}");
+
+        // the solver gives up on the first line, the lint still sees them all
+        let mut input = Input::from_reader(Cursor::new("X\ny\nZ\n"));
+        let err = input
+            .solve_linted(|lines: &mut Linted<'_, Lowercase>| {
+                lines.next();
+                Err::<usize, _>(anyhow!("gave up"))
+            })
+            .unwrap_err();
+        assert_eq!(
+            "Input has 2 problems:\n  line 1: not lowercase\n  line 3: not lowercase",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,27 @@
+//! Infrastructure shared by the day crates and the `aoc` runner.
+//!
+//! Without the `std` feature only what the solvers themselves use is built
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod build;
+#[cfg(feature = "std")]
+pub mod input;
+pub mod lint;
+#[cfg(feature = "std")]
+pub mod logging;
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod plugin;
+pub mod progress;
+#[cfg(feature = "std")]
+pub mod settings;
+pub mod stepper;
+#[cfg(feature = "serde")]
+pub mod testing;
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use anyhow;
@@ -0,0 +1,128 @@
+//! Structural checks that each day runs on its input before solving, so that
+//! every problem is reported at once rather than just the first parse error
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Display;
+
+use serde::Serialize;
+
+/// Something wrong with the input. Lines and columns count from 1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Problem {
+    /// `None` for problems with the input as a whole
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Problem {
+    pub fn whole(message: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            column: Some(column),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => {
+                write!(f, "line {line}, column {column}: {}", self.message)
+            }
+            (Some(line), None) => write!(f, "line {line}: {}", self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// A lint that goes through the input a line at a time, so that input that
+/// is streamed rather than read into memory can be checked too
+pub trait LineLint: Default {
+    /// Checks line `number`, counting from 1
+    fn line(&mut self, number: usize, line: &str);
+
+    /// Everything found, including problems with the input as a whole
+    fn finish(self) -> Vec<Problem>;
+
+    /// Lints all of `input` in one go
+    fn lint(input: &str) -> Vec<Problem> {
+        let mut lint = Self::default();
+        for (i, line) in input.lines().enumerate() {
+            lint.line(i + 1, line);
+        }
+        lint.finish()
+    }
+}
+
+/// The line and column of a byte offset into `input`
+pub fn position(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// Turns a list of problems into a single error listing all of them
+#[cfg(feature = "std")]
+pub fn check(problems: Vec<Problem>) -> anyhow::Result<()> {
+    if problems.is_empty() {
+        return Ok(());
+    }
+    let count = match problems.len() {
+        1 => "1 problem".to_string(),
+        n => format!("{n} problems"),
+    };
+    let list = problems
+        .iter()
+        .map(|problem| format!("  {problem}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Err(anyhow::anyhow!("Input has {count}:\n{list}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        assert_eq!((1, 1), position("abc\ndef", 0));
+        assert_eq!((1, 3), position("abc\ndef", 2));
+        assert_eq!((2, 1), position("abc\ndef", 4));
+        assert_eq!((2, 3), position("abc\ndef", 6));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_check() {
+        assert!(check(Vec::new()).is_ok());
+        let err = check(vec![
+            Problem::whole("no start"),
+            Problem::new(1, "empty line"),
+            Problem::at(3, 4, "unknown character 'x'"),
+        ])
+        .expect_err("should fail");
+        assert_eq!(
+            "Input has 3 problems:\n  no start\n  line 1: empty line\n  line 3, column 4: unknown character 'x'",
+            err.to_string()
+        );
+    }
+}
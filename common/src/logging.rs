@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use serde::Deserialize;
+use tracing::{Subscriber, level_filters::LevelFilter, span};
+use tracing_subscriber::{Layer, filter::Targets, layer, prelude::*, registry::LookupSpan};
+
+/// Used when neither `RUST_LOG` nor the settings give a filter. Warnings and
+/// errors only, so that the solvers' spans stay quiet unless `-v` is given
+pub const DEFAULT_FILTER: &str = "warn";
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Logging {
+    /// e.g. `info,day4=debug`
+    pub filter: Option<String>,
+    /// levels for individual modules, applied on top of `filter`, e.g.
+    /// `day4 = "warn"`
+    #[serde(default)]
+    pub modules: BTreeMap<String, String>,
+    /// print how often each instrumented function was called, and for how
+    /// long, when the run finishes
+    #[serde(default)]
+    pub summary: bool,
+    /// set from the command line, taking precedence over everything else
+    #[serde(skip)]
+    pub override_filter: Option<String>,
+    /// how many `-v`s were given, or -1 for `--quiet`
+    #[serde(skip)]
+    pub verbosity: i8,
+}
+
+/// Logging flags shared by every binary
+#[derive(Debug, Default, Clone, clap::Args)]
+pub struct LogArgs {
+    /// log more: `-v` for info messages, `-vv` for debug, `-vvv` for
+    /// everything
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+    /// only log errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    /// logging filter, e.g. `info,day4=trace`
+    #[arg(long, global = true)]
+    pub log: Option<String>,
+    /// set the level for a single module, e.g. `--log-module day4=warn`
+    #[arg(long, value_name = "MODULE=LEVEL", global = true, value_parser = crate::settings::parse_key_value)]
+    pub log_module: Vec<(String, String)>,
+    /// print call counts and total time per instrumented function at exit
+    #[arg(long, global = true)]
+    pub summary: bool,
+}
+
+impl LogArgs {
+    pub fn apply(&self, logging: &mut Logging) {
+        if let Some(filter) = &self.log {
+            logging.override_filter = Some(filter.clone());
+        }
+        logging.modules.extend(self.log_module.iter().cloned());
+        logging.summary |= self.summary;
+        logging.verbosity = match (self.quiet, self.verbose) {
+            (true, _) => -1,
+            (false, n) => n.min(i8::MAX as u8) as i8,
+        };
+    }
+}
+
+impl Logging {
+    /// The filter to use: the command line, then `RUST_LOG`, then the
+    /// settings file. Verbosity flags replace the default level, and module
+    /// levels are applied last
+    pub fn targets(&self) -> anyhow::Result<Targets> {
+        let filter = self
+            .override_filter
+            .clone()
+            .or_else(|| std::env::var("RUST_LOG").ok())
+            .or_else(|| self.filter.clone())
+            .unwrap_or_else(|| DEFAULT_FILTER.to_string());
+        let mut targets =
+            Targets::from_str(&filter).with_context(|| format!("Invalid log filter `{filter}`"))?;
+        let level = match self.verbosity {
+            ..0 => Some(LevelFilter::ERROR),
+            0 => None,
+            1 => Some(LevelFilter::INFO),
+            2 => Some(LevelFilter::DEBUG),
+            _ => Some(LevelFilter::TRACE),
+        };
+        if let Some(level) = level {
+            targets = targets.with_default(level);
+        }
+        for (module, level) in self.modules.iter() {
+            let level = LevelFilter::from_str(level)
+                .with_context(|| format!("Invalid log level `{level}` for {module}"))?;
+            targets = targets.with_target(module, level);
+        }
+        Ok(targets)
+    }
+
+    /// Installs the global subscriber. The summary, if asked for, is printed
+    /// when the returned guard is dropped
+    #[must_use = "the summary is printed when the guard is dropped"]
+    pub fn init(&self) -> anyhow::Result<LoggingGuard> {
+        let summary = self.summary.then(SpanSummary::default);
+        tracing_subscriber::registry()
+            .with(tracing_subscriber::fmt::layer().with_filter(self.targets()?))
+            .with(summary.clone())
+            .try_init()?;
+        Ok(LoggingGuard { summary })
+    }
+}
+
+/// Prints the span summary, if there is one, when dropped
+#[derive(Debug)]
+pub struct LoggingGuard {
+    summary: Option<SpanSummary>,
+}
+
+impl Drop for LoggingGuard {
+    fn drop(&mut self) {
+        if let Some(summary) = &self.summary {
+            eprint!("{}", summary.render());
+        }
+    }
+}
+
+/// Call count and time spent inside one instrumented function
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SpanStats {
+    pub calls: u64,
+    pub total: Duration,
+}
+
+/// A layer that counts the spans created for each instrumented function and
+/// adds up the time spent inside them
+#[derive(Debug, Clone, Default)]
+pub struct SpanSummary {
+    stats: Arc<Mutex<HashMap<String, SpanStats>>>,
+}
+
+/// stored in each span's extensions while it is entered
+struct Entered(Instant);
+
+fn span_name(metadata: &tracing::Metadata<'_>) -> String {
+    format!("{}::{}", metadata.target(), metadata.name())
+}
+
+impl SpanSummary {
+    /// Everything recorded so far, most expensive first
+    pub fn stats(&self) -> Vec<(String, SpanStats)> {
+        let mut stats = self
+            .stats
+            .lock()
+            .expect("span summary poisoned")
+            .iter()
+            .map(|(name, stats)| (name.clone(), *stats))
+            .collect::<Vec<_>>();
+        stats.sort_by(|a, b| b.1.total.cmp(&a.1.total).then_with(|| a.0.cmp(&b.0)));
+        stats
+    }
+
+    pub fn render(&self) -> String {
+        let stats = self.stats();
+        let width = stats
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0)
+            .max("function".len());
+        let mut output = format!("{:<width$} {:>10} {:>12}\n", "function", "calls", "total");
+        for (name, stats) in stats {
+            output.push_str(&format!(
+                "{name:<width$} {:>10} {:>12}\n",
+                stats.calls,
+                format!("{:.2?}", stats.total)
+            ));
+        }
+        output
+    }
+}
+
+impl<S> Layer<S> for SpanSummary
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(
+        &self,
+        attrs: &span::Attributes<'_>,
+        _id: &span::Id,
+        _ctx: layer::Context<'_, S>,
+    ) {
+        self.stats
+            .lock()
+            .expect("span summary poisoned")
+            .entry(span_name(attrs.metadata()))
+            .or_default()
+            .calls += 1;
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: layer::Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().replace(Entered(Instant::now()));
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: layer::Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Some(Entered(start)) = span.extensions_mut().remove::<Entered>() else {
+            return;
+        };
+        self.stats
+            .lock()
+            .expect("span summary poisoned")
+            .entry(span_name(span.metadata()))
+            .or_default()
+            .total += start.elapsed();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tracing::instrument]
+    fn leaf(n: u32) -> u32 {
+        n + 1
+    }
+
+    #[tracing::instrument]
+    fn branch() -> u32 {
+        (0..3).map(leaf).sum()
+    }
+
+    #[test]
+    fn test_span_summary() {
+        let summary = SpanSummary::default();
+        let subscriber = tracing_subscriber::registry().with(summary.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            branch();
+            branch();
+        });
+        let stats = summary.stats().into_iter().collect::<HashMap<_, _>>();
+        assert_eq!(2, stats["common::logging::test::branch"].calls);
+        assert_eq!(6, stats["common::logging::test::leaf"].calls);
+        assert!(
+            stats["common::logging::test::branch"].total
+                >= stats["common::logging::test::leaf"].total
+        );
+        let rendered = summary.render();
+        assert!(rendered.starts_with("function "));
+        assert_eq!(3, rendered.lines().count());
+    }
+
+    #[test]
+    fn test_default_filter() {
+        let enabled = |logging: &Logging, level| {
+            logging
+                .targets()
+                .expect("Invalid filter")
+                .would_enable("day7", &level)
+        };
+        // day7's info spans wrote hundreds of kilobytes for a plain run
+        let mut logging = Logging::default();
+        assert!(!enabled(&logging, tracing::Level::INFO));
+        assert!(enabled(&logging, tracing::Level::WARN));
+
+        logging.verbosity = 1;
+        assert!(enabled(&logging, tracing::Level::INFO));
+        assert!(!enabled(&logging, tracing::Level::DEBUG));
+        logging.verbosity = 3;
+        assert!(enabled(&logging, tracing::Level::TRACE));
+    }
+
+    #[test]
+    fn test_targets() {
+        let enabled = |logging: &Logging, target: &str, level| {
+            logging
+                .targets()
+                .expect("Invalid filter")
+                .would_enable(target, &level)
+        };
+        let mut logging = Logging {
+            override_filter: Some("info,day4=trace".to_string()),
+            ..Default::default()
+        };
+        assert!(enabled(&logging, "day4", tracing::Level::TRACE));
+        assert!(!enabled(&logging, "day5", tracing::Level::DEBUG));
+
+        logging.verbosity = 2;
+        assert!(enabled(&logging, "day5", tracing::Level::DEBUG));
+        assert!(!enabled(&logging, "day5", tracing::Level::TRACE));
+
+        logging.verbosity = -1;
+        assert!(!enabled(&logging, "day5", tracing::Level::WARN));
+        assert!(enabled(&logging, "day4", tracing::Level::TRACE));
+
+        logging
+            .modules
+            .insert("day4::Map".to_string(), "warn".to_string());
+        assert!(!enabled(&logging, "day4::Map", tracing::Level::INFO));
+        assert!(enabled(&logging, "day4", tracing::Level::INFO));
+
+        logging
+            .modules
+            .insert("day4".to_string(), "loud".to_string());
+        assert!(logging.targets().is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! Counters for how much work a solver did, so that algorithm changes can be
+//! compared by more than wall time.
+//!
+//! Solvers add to named counters with [`count`], and a runner collects
+//! everything reported while solving with [`measure`]. Outside of
+//! [`measure`] counts are dropped, so they cost next to nothing. Without the
+//! `std` feature there's nowhere to keep them, so nothing is ever counted
+
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+
+/// Counter values by name
+pub type Counters = BTreeMap<&'static str, u64>;
+
+#[cfg(feature = "std")]
+thread_local! {
+    static COUNTERS: RefCell<Option<Counters>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, returning everything it reported along with its result.
+/// Measurements can be nested, and the outer one sees the inner one's counts
+#[cfg(feature = "std")]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Counters) {
+    let outer = COUNTERS.replace(Some(Counters::new()));
+    let result = f();
+    let counters = COUNTERS
+        .replace(outer)
+        .expect("metrics counters removed while measuring");
+    COUNTERS.with_borrow_mut(|outer| {
+        if let Some(outer) = outer {
+            for (&name, &value) in counters.iter() {
+                *outer.entry(name).or_default() += value;
+            }
+        }
+    });
+    (result, counters)
+}
+
+#[cfg(not(feature = "std"))]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Counters) {
+    (f(), Counters::new())
+}
+
+/// Adds `n` to the counter `name`
+#[cfg(feature = "std")]
+pub fn count(name: &'static str, n: u64) {
+    COUNTERS.with_borrow_mut(|counters| {
+        if let Some(counters) = counters {
+            *counters.entry(name).or_default() += n;
+        }
+    })
+}
+
+#[cfg(not(feature = "std"))]
+pub fn count(_name: &'static str, _n: u64) {}
+
+/// Formats the counters on a single line, e.g. `candidates=12, rows=3`
+pub fn render(counters: &Counters) -> String {
+    counters
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        // nothing is kept outside of a measurement
+        count("steps", 1);
+        let ((), counters) = measure(|| {
+            count("steps", 2);
+            count("steps", 3);
+            count("rows", 1);
+        });
+        assert_eq!(Counters::from([("rows", 1), ("steps", 5)]), counters);
+        assert_eq!("rows=1, steps=5", render(&counters));
+    }
+
+    #[test]
+    fn test_nested() {
+        let (inner, outer) = measure(|| {
+            count("steps", 1);
+            let ((), inner) = measure(|| count("steps", 2));
+            inner
+        });
+        assert_eq!(Counters::from([("steps", 2)]), inner);
+        assert_eq!(Counters::from([("steps", 3)]), outer);
+    }
+}
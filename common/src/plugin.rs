@@ -0,0 +1,334 @@
+//! A stable C ABI for loading day solvers from dynamic libraries at runtime.
+//!
+//! A day crate exposes itself as a plugin with [`declare_plugin!`] behind its
+//! `plugin` feature, and is built as a dynamic library with e.g.
+//!
+//! ```sh
+//! cargo rustc --lib --release --features plugin --crate-type cdylib
+//! ```
+//!
+//! The host looks up [`DECLARATION_SYMBOL`] in the library and must check
+//! [`PluginDecl::abi_version`] against [`ABI_VERSION`] before using anything
+//! else in the declaration.
+
+use std::{
+    ffi::c_void,
+    mem::ManuallyDrop,
+    panic::{AssertUnwindSafe, catch_unwind},
+};
+
+/// Bumped whenever the layout of [`PluginDecl`] or the meaning of any of its
+/// functions changes
+pub const ABI_VERSION: u32 = 1;
+
+/// The name of the function returning the plugin's [`PluginDecl`]
+pub const DECLARATION_SYMBOL: &[u8] = b"aoc_plugin_declaration";
+
+pub type DeclarationFn = unsafe extern "C" fn() -> *const PluginDecl;
+
+pub const STATUS_OK: i32 = 0;
+pub const STATUS_ERROR: i32 = 1;
+
+/// A string allocated by the plugin. It must be released with the plugin's
+/// [`PluginDecl::free_string`]
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawString {
+    pub ptr: *mut u8,
+    pub len: usize,
+    pub cap: usize,
+}
+
+impl RawString {
+    pub const fn empty() -> Self {
+        Self {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+            cap: 0,
+        }
+    }
+
+    fn from_string(s: String) -> Self {
+        let mut s = ManuallyDrop::new(s);
+        Self {
+            ptr: s.as_mut_ptr(),
+            len: s.len(),
+            cap: s.capacity(),
+        }
+    }
+
+    /// Copies the contents into a host-owned string
+    ///
+    /// # Safety
+    /// `self` must have been filled in by the plugin and not yet freed
+    pub unsafe fn to_string_lossy(&self) -> String {
+        if self.ptr.is_null() {
+            return String::new();
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(self.ptr, self.len) };
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+pub type ParseFn = unsafe extern "C" fn(
+    input: *const u8,
+    len: usize,
+    parsed: *mut *mut c_void,
+    error: *mut RawString,
+) -> i32;
+pub type SolveFn = unsafe extern "C" fn(parsed: *const c_void, answer: *mut RawString) -> i32;
+pub type FreeParsedFn = unsafe extern "C" fn(parsed: *mut c_void);
+pub type FreeStringFn = unsafe extern "C" fn(s: RawString);
+
+/// Everything the host needs to drive a plugin. `abi_version` must stay the
+/// first field so that it can be checked on plugins built against any version
+#[repr(C)]
+pub struct PluginDecl {
+    pub abi_version: u32,
+    pub day: u32,
+    /// utf-8 version of the day crate, not nul-terminated
+    pub version: *const u8,
+    pub version_len: usize,
+    /// parses the input into an opaque handle, or fills in `error`
+    pub parse: ParseFn,
+    /// solves a part from a parsed handle, filling in `answer` with either
+    /// the answer or an error message depending on the returned status
+    pub part1: SolveFn,
+    pub part2: SolveFn,
+    pub free_parsed: FreeParsedFn,
+    pub free_string: FreeStringFn,
+}
+
+// the declaration only holds pointers to static data and functions
+unsafe impl Sync for PluginDecl {}
+
+/// Implemented (through [`declare_plugin!`]) by every day crate that can be
+/// built as a plugin
+pub trait Solver {
+    type Parsed;
+    const DAY: u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed>;
+    fn part1(parsed: &Self::Parsed) -> anyhow::Result<String>;
+    fn part2(parsed: &Self::Parsed) -> anyhow::Result<String>;
+}
+
+impl PluginDecl {
+    pub const fn new<S: Solver>(version: &'static str) -> Self {
+        Self {
+            abi_version: ABI_VERSION,
+            day: S::DAY,
+            version: version.as_ptr(),
+            version_len: version.len(),
+            parse: parse_shim::<S>,
+            part1: part1_shim::<S>,
+            part2: part2_shim::<S>,
+            free_parsed: free_parsed_shim::<S>,
+            free_string: free_string_shim,
+        }
+    }
+
+    /// # Safety
+    /// the declaration must come from a plugin with a matching ABI version
+    pub unsafe fn version(&self) -> String {
+        let bytes = unsafe { std::slice::from_raw_parts(self.version, self.version_len) };
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+fn describe_panic(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(s) => format!("plugin panicked: {s}"),
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(s) => format!("plugin panicked: {s}"),
+            Err(_) => "plugin panicked".to_string(),
+        },
+    }
+}
+
+/// Runs `f` without letting a panic unwind across the ABI boundary
+fn guarded<T>(f: impl FnOnce() -> anyhow::Result<T>) -> Result<T, String> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(format!("{e:#}")),
+        Err(payload) => Err(describe_panic(payload)),
+    }
+}
+
+unsafe extern "C" fn parse_shim<S: Solver>(
+    input: *const u8,
+    len: usize,
+    parsed: *mut *mut c_void,
+    error: *mut RawString,
+) -> i32 {
+    let bytes = unsafe { std::slice::from_raw_parts(input, len) };
+    let result = guarded(|| S::parse(std::str::from_utf8(bytes)?));
+    match result {
+        Ok(value) => {
+            unsafe { *parsed = Box::into_raw(Box::new(value)).cast() };
+            STATUS_OK
+        }
+        Err(message) => {
+            unsafe { *error = RawString::from_string(message) };
+            STATUS_ERROR
+        }
+    }
+}
+
+unsafe fn solve_shim<S: Solver>(
+    parsed: *const c_void,
+    answer: *mut RawString,
+    solve: fn(&S::Parsed) -> anyhow::Result<String>,
+) -> i32 {
+    let parsed = unsafe { &*parsed.cast::<S::Parsed>() };
+    let (status, text) = match guarded(|| solve(parsed)) {
+        Ok(text) => (STATUS_OK, text),
+        Err(message) => (STATUS_ERROR, message),
+    };
+    unsafe { *answer = RawString::from_string(text) };
+    status
+}
+
+unsafe extern "C" fn part1_shim<S: Solver>(parsed: *const c_void, answer: *mut RawString) -> i32 {
+    unsafe { solve_shim::<S>(parsed, answer, S::part1) }
+}
+
+unsafe extern "C" fn part2_shim<S: Solver>(parsed: *const c_void, answer: *mut RawString) -> i32 {
+    unsafe { solve_shim::<S>(parsed, answer, S::part2) }
+}
+
+unsafe extern "C" fn free_parsed_shim<S: Solver>(parsed: *mut c_void) {
+    if !parsed.is_null() {
+        drop(unsafe { Box::from_raw(parsed.cast::<S::Parsed>()) });
+    }
+}
+
+unsafe extern "C" fn free_string_shim(s: RawString) {
+    if !s.ptr.is_null() {
+        drop(unsafe { String::from_raw_parts(s.ptr, s.len, s.cap) });
+    }
+}
+
+/// Exports a day's solver through the plugin ABI.
+///
+/// ```ignore
+/// common::declare_plugin! {
+///     day: 7,
+///     parsed: Manifold,
+///     parse: Manifold::parse,
+///     part1: |manifold: &Manifold| Ok(manifold.clone().run().to_string()),
+///     part2: |manifold: &Manifold| Ok(manifold.clone().timelines().to_string()),
+/// }
+/// ```
+#[macro_export]
+macro_rules! declare_plugin {
+    (
+        day: $day:expr,
+        parsed: $parsed:ty,
+        parse: $parse:expr,
+        part1: $part1:expr,
+        part2: $part2:expr $(,)?
+    ) => {
+        #[doc(hidden)]
+        pub struct AocPlugin;
+
+        impl $crate::plugin::Solver for AocPlugin {
+            type Parsed = $parsed;
+            const DAY: u32 = $day;
+
+            fn parse(input: &str) -> $crate::anyhow::Result<$parsed> {
+                ($parse)(input)
+            }
+
+            fn part1(parsed: &$parsed) -> $crate::anyhow::Result<String> {
+                ($part1)(parsed)
+            }
+
+            fn part2(parsed: &$parsed) -> $crate::anyhow::Result<String> {
+                ($part2)(parsed)
+            }
+        }
+
+        #[doc(hidden)]
+        #[unsafe(no_mangle)]
+        pub extern "C" fn aoc_plugin_declaration() -> *const $crate::plugin::PluginDecl {
+            const DECLARATION: $crate::plugin::PluginDecl =
+                $crate::plugin::PluginDecl::new::<AocPlugin>(env!("CARGO_PKG_VERSION"));
+            &DECLARATION
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Doubler;
+
+    impl Solver for Doubler {
+        type Parsed = Vec<i64>;
+        const DAY: u32 = 99;
+
+        fn parse(input: &str) -> anyhow::Result<Vec<i64>> {
+            Ok(input
+                .lines()
+                .map(|line| line.parse::<i64>())
+                .collect::<Result<_, _>>()?)
+        }
+
+        fn part1(parsed: &Vec<i64>) -> anyhow::Result<String> {
+            Ok((parsed.iter().sum::<i64>() * 2).to_string())
+        }
+
+        fn part2(_parsed: &Vec<i64>) -> anyhow::Result<String> {
+            panic!("not implemented")
+        }
+    }
+
+    const DECL: PluginDecl = PluginDecl::new::<Doubler>("1.2.3");
+
+    unsafe fn take(decl: &PluginDecl, s: RawString) -> String {
+        let text = unsafe { s.to_string_lossy() };
+        unsafe { (decl.free_string)(s) };
+        text
+    }
+
+    #[test]
+    fn test_round_trip() {
+        unsafe {
+            assert_eq!(ABI_VERSION, DECL.abi_version);
+            assert_eq!(99, DECL.day);
+            assert_eq!("1.2.3", DECL.version());
+
+            let input = "1\n2\n3\n";
+            let mut parsed = std::ptr::null_mut();
+            let mut error = RawString::empty();
+            let status = (DECL.parse)(input.as_ptr(), input.len(), &mut parsed, &mut error);
+            assert_eq!(STATUS_OK, status);
+
+            let mut answer = RawString::empty();
+            assert_eq!(STATUS_OK, (DECL.part1)(parsed, &mut answer));
+            assert_eq!("12", take(&DECL, answer));
+
+            // panics are reported as errors rather than unwinding into the host
+            let mut answer = RawString::empty();
+            assert_eq!(STATUS_ERROR, (DECL.part2)(parsed, &mut answer));
+            assert_eq!("plugin panicked: not implemented", take(&DECL, answer));
+
+            (DECL.free_parsed)(parsed);
+        }
+    }
+
+    #[test]
+    fn test_parse_error() {
+        unsafe {
+            let input = "1\nx\n";
+            let mut parsed = std::ptr::null_mut();
+            let mut error = RawString::empty();
+            let status = (DECL.parse)(input.as_ptr(), input.len(), &mut parsed, &mut error);
+            assert_eq!(STATUS_ERROR, status);
+            assert!(parsed.is_null());
+            assert!(take(&DECL, error).contains("invalid digit"));
+        }
+    }
+}
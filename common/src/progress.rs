@@ -0,0 +1,229 @@
+//! Progress reports and time limits for long-running solvers.
+//!
+//! A runner wraps each phase of solving in [`run_phase`], and the solvers'
+//! main loops call [`tick`] once per step. Ticks are passed on to the
+//! progress callback, and once the phase's time is up they tell the loop to
+//! stop early. The result of a cancelled phase is thrown away, so solvers
+//! don't need to return errors for this. Outside of [`run_phase`] ticks do
+//! nothing and never cancel anything.
+//!
+//! Phases that never tick (including solvers loaded from plugins) can't be
+//! interrupted, so their timeout is only noticed once they finish. Without
+//! the `std` feature there are no phases, and ticks always carry on
+
+#[cfg(feature = "std")]
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "std")]
+/// A single step of a long-running loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Update<'a> {
+    /// the phase being run, e.g. `part 2`
+    pub phase: &'a str,
+    /// what the loop is working through, e.g. `rows`
+    pub task: &'static str,
+    pub done: usize,
+    /// `None` if the loop doesn't know how many steps there will be
+    pub total: Option<usize>,
+    pub elapsed: Duration,
+}
+
+#[cfg(feature = "std")]
+pub type Callback = Arc<dyn Fn(&Update<'_>) + Send + Sync>;
+
+#[cfg(feature = "std")]
+/// How each phase is watched. The timeout applies to every phase separately
+#[derive(Clone, Default)]
+pub struct Limits {
+    pub timeout: Option<Duration>,
+    pub progress: Option<Callback>,
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Debug for Limits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Limits")
+            .field("timeout", &self.timeout)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+/// A phase that ran for longer than it was allowed to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedOut {
+    pub phase: String,
+    pub timeout: Duration,
+}
+
+#[cfg(feature = "std")]
+impl Display for TimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} timed out after {:.2?}", self.phase, self.timeout)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TimedOut {}
+
+#[cfg(feature = "std")]
+/// The phase running on this thread
+struct Monitor {
+    phase: String,
+    started: Instant,
+    limits: Limits,
+    cancelled: bool,
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static MONITOR: RefCell<Option<Monitor>> = const { RefCell::new(None) };
+}
+
+#[cfg(feature = "std")]
+/// Runs one phase of solving within `limits`
+pub fn run_phase<T>(
+    phase: impl Into<String>,
+    limits: &Limits,
+    f: impl FnOnce() -> T,
+) -> Result<T, TimedOut> {
+    let monitor = Monitor {
+        phase: phase.into(),
+        started: Instant::now(),
+        limits: limits.clone(),
+        cancelled: false,
+    };
+    let outer = MONITOR.replace(Some(monitor));
+    let result = f();
+    let monitor = MONITOR
+        .replace(outer)
+        .expect("progress monitor removed while running");
+    let timed_out = monitor.cancelled
+        || monitor
+            .limits
+            .timeout
+            .is_some_and(|timeout| monitor.started.elapsed() > timeout);
+    match (timed_out, monitor.limits.timeout) {
+        (true, Some(timeout)) => Err(TimedOut {
+            phase: monitor.phase,
+            timeout,
+        }),
+        _ => Ok(result),
+    }
+}
+
+/// Reports that `done` steps of `task` have finished. Returns `false` once
+/// the phase has run out of time, after which the loop should stop and
+/// return whatever it has
+#[cfg(feature = "std")]
+pub fn tick(task: &'static str, done: usize, total: Option<usize>) -> bool {
+    MONITOR.with_borrow_mut(|monitor| {
+        let Some(monitor) = monitor else {
+            return true;
+        };
+        let elapsed = monitor.started.elapsed();
+        if let Some(progress) = &monitor.limits.progress {
+            progress(&Update {
+                phase: &monitor.phase,
+                task,
+                done,
+                total,
+                elapsed,
+            });
+        }
+        if monitor
+            .limits
+            .timeout
+            .is_some_and(|timeout| elapsed > timeout)
+        {
+            monitor.cancelled = true;
+        }
+        !monitor.cancelled
+    })
+}
+
+#[cfg(not(feature = "std"))]
+pub fn tick(_task: &'static str, _done: usize, _total: Option<usize>) -> bool {
+    true
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn count(limit: usize) -> usize {
+        let mut done = 0;
+        while done < limit && tick("numbers", done, Some(limit)) {
+            done += 1;
+        }
+        done
+    }
+
+    #[test]
+    fn test_unlimited() {
+        assert_eq!(10, count(10));
+        assert_eq!(Ok(10), run_phase("count", &Limits::default(), || count(10)));
+    }
+
+    #[test]
+    fn test_progress() {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let limits = Limits {
+            timeout: None,
+            progress: Some({
+                let updates = updates.clone();
+                Arc::new(move |update: &Update<'_>| {
+                    updates
+                        .lock()
+                        .unwrap()
+                        .push((update.phase.to_string(), update.done));
+                })
+            }),
+        };
+        assert_eq!(Ok(3), run_phase("part 1", &limits, || count(3)));
+        assert_eq!(
+            vec![
+                ("part 1".to_string(), 0),
+                ("part 1".to_string(), 1),
+                ("part 1".to_string(), 2)
+            ],
+            *updates.lock().unwrap()
+        );
+        // nothing is reported outside of a phase
+        count(3);
+        assert_eq!(3, updates.lock().unwrap().len());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let limits = Limits {
+            timeout: Some(Duration::ZERO),
+            progress: None,
+        };
+        let err = run_phase("part 2", &limits, || {
+            std::thread::sleep(Duration::from_millis(1));
+            count(usize::MAX)
+        })
+        .expect_err("should time out");
+        assert_eq!("part 2", err.phase);
+        assert!(err.to_string().starts_with("part 2 timed out after"));
+
+        // a phase that doesn't tick is only caught once it's finished
+        assert!(
+            run_phase("lint", &limits, || std::thread::sleep(
+                Duration::from_millis(1)
+            ))
+            .is_err()
+        );
+        // the limits only last as long as the phase
+        assert_eq!(5, count(5));
+    }
+}
@@ -0,0 +1,351 @@
+//! Project-wide settings read from an `aoc.toml` at the root of the checkout,
+//! so that tweaking a run doesn't mean editing the source
+//!
+//! ```toml
+//! [logging]
+//! filter = "info,day4=debug"
+//!
+//! [day1]
+//! input = "day1/part1.input"
+//! dial_range = 100
+//!
+//! [day4.bench.part2_iterate]
+//! sample_count = 20
+//! ```
+//!
+//! Relative paths are relative to the directory holding `aoc.toml`. Every day
+//! binary accepts the flags in [`RunArgs`], which take precedence over the
+//! file.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+use serde::{Deserialize, de::DeserializeOwned};
+
+use crate::logging::{LogArgs, Logging, LoggingGuard};
+
+pub const FILE_NAME: &str = "aoc.toml";
+
+#[derive(Debug, Default)]
+pub struct Settings {
+    /// directory that relative paths are resolved against
+    dir: PathBuf,
+    pub logging: Logging,
+    days: BTreeMap<u32, toml::Table>,
+}
+
+/// Settings for a single day
+#[derive(Debug, Default, Deserialize)]
+pub struct DaySettings {
+    pub input: Option<PathBuf>,
+    /// sample count for every benchmark of the day
+    pub sample_count: Option<u32>,
+    /// per-benchmark settings, keyed by benchmark name
+    #[serde(default)]
+    pub bench: BTreeMap<String, BenchSettings>,
+    /// anything specific to the day, e.g. day1's `dial_range`
+    #[serde(flatten)]
+    pub options: toml::Table,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BenchSettings {
+    pub sample_count: Option<u32>,
+}
+
+impl Settings {
+    /// Looks for `aoc.toml` in the current directory and its parents, falling
+    /// back to the defaults if there is none
+    pub fn discover() -> anyhow::Result<Self> {
+        let cwd = std::env::current_dir()?;
+        match cwd
+            .ancestors()
+            .map(|dir| dir.join(FILE_NAME))
+            .find(|path| path.is_file())
+        {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read settings file {}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        Self::from_string(&contents, dir)
+            .with_context(|| format!("Failed to parse settings file {}", path.display()))
+    }
+
+    pub fn from_string(input: &str, dir: PathBuf) -> anyhow::Result<Self> {
+        let table: toml::Table = toml::from_str(input)?;
+        let mut settings = Self {
+            dir,
+            ..Default::default()
+        };
+        for (key, value) in table {
+            if key == "logging" {
+                settings.logging = value.try_into()?;
+                continue;
+            }
+            let day = key
+                .strip_prefix("day")
+                .and_then(|day| day.parse().ok())
+                .ok_or_else(|| anyhow!("Unknown section [{key}]"))?;
+            let toml::Value::Table(table) = value else {
+                return Err(anyhow!("[{key}] must be a table"));
+            };
+            // catch mistakes now rather than when the day is run
+            DaySettings::deserialize(table.clone())
+                .with_context(|| format!("Invalid settings for [{key}]"))?;
+            settings.days.insert(day, table);
+        }
+        Ok(settings)
+    }
+
+    /// Overrides a single setting for a day. The value is parsed as TOML, or
+    /// taken as a string if it isn't valid TOML
+    pub fn set(&mut self, day: u32, key: &str, value: &str) {
+        let value = toml::from_str::<toml::Table>(&format!("value = {value}"))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .unwrap_or_else(|| toml::Value::String(value.to_string()));
+        self.days
+            .entry(day)
+            .or_default()
+            .insert(key.to_string(), value);
+    }
+
+    pub fn day(&self, day: u32) -> anyhow::Result<DaySettings> {
+        let mut settings = match self.days.get(&day) {
+            Some(table) => DaySettings::deserialize(table.clone())
+                .with_context(|| format!("Invalid settings for [day{day}]"))?,
+            None => DaySettings::default(),
+        };
+        settings.input = settings.input.map(|input| self.dir.join(input));
+        Ok(settings)
+    }
+}
+
+impl DaySettings {
+    /// The configured input, or `default` relative to the current directory
+    pub fn input_path(&self, default: impl AsRef<Path>) -> PathBuf {
+        self.input
+            .clone()
+            .unwrap_or_else(|| default.as_ref().to_path_buf())
+    }
+
+    pub fn option<T: DeserializeOwned>(&self, key: &str) -> anyhow::Result<Option<T>> {
+        self.options
+            .get(key)
+            .map(|value| value.clone().try_into())
+            .transpose()
+            .with_context(|| format!("Invalid value for {key}"))
+    }
+
+    /// Fails on any option that isn't in `known`, so a misspelt key in
+    /// `aoc.toml` or `--set` doesn't quietly do nothing
+    pub fn check_options(&self, known: &[&str]) -> anyhow::Result<()> {
+        let unknown = self
+            .options
+            .keys()
+            .filter(|key| !known.contains(&key.as_str()))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        match unknown.as_slice() {
+            [] => Ok(()),
+            _ if known.is_empty() => Err(anyhow!(
+                "Unknown options {}, this day takes none",
+                unknown.join(", ")
+            )),
+            _ => Err(anyhow!(
+                "Unknown options {}, expected one of {}",
+                unknown.join(", "),
+                known.join(", ")
+            )),
+        }
+    }
+
+    /// The sample count to use for a benchmark, most specific setting first
+    pub fn sample_count(&self, bench: &str, default: u32) -> u32 {
+        self.bench
+            .get(bench)
+            .and_then(|bench| bench.sample_count)
+            .or(self.sample_count)
+            .unwrap_or(default)
+    }
+}
+
+/// Command line flags shared by every day binary
+#[derive(Debug, Default, clap::Parser)]
+pub struct RunArgs {
+    /// settings file to use instead of searching for `aoc.toml`
+    #[arg(long)]
+    pub settings: Option<PathBuf>,
+    /// input file to solve
+    #[arg(long)]
+    pub input: Option<PathBuf>,
+    #[command(flatten)]
+    pub logging: LogArgs,
+    /// override a day setting, e.g. `--set dial_range=10`
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub overrides: Vec<(String, String)>,
+}
+
+pub(crate) fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got `{s}`"))
+}
+
+impl RunArgs {
+    /// Loads the settings for `day` with the flags applied on top, checking
+    /// the day's options against `options`
+    pub fn settings(&self, day: u32, options: &[&str]) -> anyhow::Result<(Settings, DaySettings)> {
+        let mut settings = match &self.settings {
+            Some(path) => Settings::load(path)?,
+            None => Settings::discover()?,
+        };
+        for (key, value) in self.overrides.iter() {
+            settings.set(day, key, value);
+        }
+        self.logging.apply(&mut settings.logging);
+        let mut day_settings = settings.day(day)?;
+        if let Some(input) = &self.input {
+            day_settings.input = Some(input.clone());
+        }
+        day_settings
+            .check_options(options)
+            .with_context(|| format!("Invalid settings for [day{day}]"))?;
+        Ok((settings, day_settings))
+    }
+}
+
+/// The settings for `day` from the discovered `aoc.toml`, which may only set
+/// the given `options`. Used by the benchmarks, which don't take our flags
+pub fn load_day(day: u32, options: &[&str]) -> anyhow::Result<DaySettings> {
+    let settings = Settings::discover()?.day(day)?;
+    settings
+        .check_options(options)
+        .with_context(|| format!("Invalid settings for [day{day}]"))?;
+    Ok(settings)
+}
+
+/// Parses the command line, loads the settings for `day` and sets up logging.
+/// `options` lists every key the day reads with [`DaySettings::option`], any
+/// other key is an error. Meant to be the first thing a day's `main` does,
+/// keeping the guard alive until the end
+pub fn init(day: u32, options: &[&str]) -> anyhow::Result<(DaySettings, LoggingGuard)> {
+    let args = <RunArgs as clap::Parser>::parse();
+    let (settings, day_settings) = args.settings(day, options)?;
+    let guard = settings.logging.init()?;
+    Ok((day_settings, guard))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SETTINGS: &str = "
+[logging]
+filter = \"info,day4=debug\"
+
+[day1]
+input = \"day1/part1.input\"
+initial_position = 20
+dial_range = 10
+
+[day4]
+sample_count = 100
+
+[day4.bench.part2_iterate]
+sample_count = 20
+";
+
+    #[test]
+    fn test_parse() {
+        let settings =
+            Settings::from_string(SETTINGS, PathBuf::from("/repo")).expect("Failed to parse");
+        assert_eq!(Some("info,day4=debug"), settings.logging.filter.as_deref());
+
+        let day1 = settings.day(1).expect("Invalid day1");
+        assert_eq!(
+            PathBuf::from("/repo/day1/part1.input"),
+            day1.input_path("x")
+        );
+        assert_eq!(Some(20), day1.option::<i32>("initial_position").unwrap());
+        assert_eq!(None, day1.option::<i32>("target").unwrap());
+        assert!(day1.option::<String>("dial_range").is_err());
+
+        let day4 = settings.day(4).expect("Invalid day4");
+        assert_eq!(20, day4.sample_count("part2_iterate", 1));
+        assert_eq!(100, day4.sample_count("part1", 1));
+
+        let day6 = settings.day(6).expect("Invalid day6");
+        assert_eq!(PathBuf::from("input"), day6.input_path("input"));
+        assert_eq!(1000, day6.sample_count("parse", 1000));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Settings::from_string("[day]\ninput = \"x\"", PathBuf::new()).is_err());
+        assert!(Settings::from_string("[day3]\nsample_count = \"x\"", PathBuf::new()).is_err());
+        assert!(Settings::from_string("[day3.bench.part1]\ncount = 1", PathBuf::new()).is_err());
+        assert!(Settings::from_string("[logging]\nlevel = 1", PathBuf::new()).is_err());
+    }
+
+    #[test]
+    fn test_overrides() {
+        let args = <RunArgs as clap::Parser>::try_parse_from([
+            "day1",
+            "--settings",
+            "/nonexistent/aoc.toml",
+        ])
+        .unwrap();
+        assert!(args.settings(1, &[]).is_err());
+
+        let mut settings =
+            Settings::from_string(SETTINGS, PathBuf::from("/repo")).expect("Failed to parse");
+        settings.set(1, "dial_range", "12");
+        settings.set(1, "input", "other.input");
+        let day1 = settings.day(1).unwrap();
+        assert_eq!(Some(12), day1.option::<i32>("dial_range").unwrap());
+        assert_eq!(PathBuf::from("/repo/other.input"), day1.input_path("x"));
+
+        let args = <RunArgs as clap::Parser>::try_parse_from([
+            "day1",
+            "--input",
+            "mine.input",
+            "--set",
+            "dial_range = 7",
+        ])
+        .unwrap();
+        assert_eq!(
+            vec![("dial_range".to_string(), "7".to_string())],
+            args.overrides
+        );
+        assert!(<RunArgs as clap::Parser>::try_parse_from(["day1", "--set", "nope"]).is_err());
+    }
+
+    #[test]
+    fn test_unknown_options() {
+        let mut settings =
+            Settings::from_string(SETTINGS, PathBuf::from("/repo")).expect("Failed to parse");
+        let day1 = settings.day(1).unwrap();
+        assert!(
+            day1.check_options(&["initial_position", "dial_range", "target"])
+                .is_ok()
+        );
+        assert!(day1.check_options(&["dial_range"]).is_err());
+        assert!(day1.check_options(&[]).is_err());
+        assert!(settings.day(4).unwrap().check_options(&[]).is_ok());
+
+        settings.set(4, "dial_rnage", "7");
+        let day4 = settings.day(4).unwrap();
+        let e = day4.check_options(&["dial_range"]).unwrap_err();
+        assert!(e.to_string().contains("dial_rnage"), "{e}");
+    }
+}
@@ -0,0 +1,187 @@
+//! Simulations that advance one step at a time.
+//!
+//! The days that simulate something implement [`Stepper`], and [`run`] drives
+//! any of them while showing every step to an [`Observer`]. That way
+//! visualizers, progress reports and step limits are written once rather than
+//! for every day
+
+use core::ops::ControlFlow;
+
+pub trait Stepper {
+    /// What a single step changed, e.g. how often the beams split
+    type Delta;
+
+    /// Advances by one step, returning `None` once there's nothing left to do
+    fn step(&mut self) -> Option<Self::Delta>;
+
+    /// Whether the next step would return `None`
+    fn is_done(&self) -> bool;
+}
+
+/// Watches a [`Stepper`] as it's [`run`]
+pub trait Observer<S: Stepper + ?Sized> {
+    /// Called before step `step` is taken. Breaking stops the run without
+    /// taking it
+    fn before(&mut self, _step: usize, _state: &S) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called after each step with the step's index, counting from 0, the
+    /// stepper as it is after the step, and what the step changed. Breaking
+    /// stops the run
+    fn observe(&mut self, step: usize, state: &S, delta: &S::Delta) -> ControlFlow<()>;
+}
+
+impl<S, F> Observer<S> for F
+where
+    S: Stepper + ?Sized,
+    F: FnMut(usize, &S, &S::Delta) -> ControlFlow<()>,
+{
+    fn observe(&mut self, step: usize, state: &S, delta: &S::Delta) -> ControlFlow<()> {
+        self(step, state, delta)
+    }
+}
+
+/// Watches nothing
+impl<S: Stepper + ?Sized> Observer<S> for () {
+    fn observe(&mut self, _step: usize, _state: &S, _delta: &S::Delta) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// Both observers, in order. The run stops if either of them breaks
+impl<S, A, B> Observer<S> for (A, B)
+where
+    S: Stepper + ?Sized,
+    A: Observer<S>,
+    B: Observer<S>,
+{
+    fn before(&mut self, step: usize, state: &S) -> ControlFlow<()> {
+        let first = self.0.before(step, state);
+        let second = self.1.before(step, state);
+        match first.is_break() || second.is_break() {
+            true => ControlFlow::Break(()),
+            false => ControlFlow::Continue(()),
+        }
+    }
+
+    fn observe(&mut self, step: usize, state: &S, delta: &S::Delta) -> ControlFlow<()> {
+        let first = self.0.observe(step, state, delta);
+        let second = self.1.observe(step, state, delta);
+        match first.is_break() || second.is_break() {
+            true => ControlFlow::Break(()),
+            false => ControlFlow::Continue(()),
+        }
+    }
+}
+
+/// Stops the run after this many steps, so `Limit(0)` takes none
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limit(pub usize);
+
+impl<S: Stepper + ?Sized> Observer<S> for Limit {
+    fn before(&mut self, step: usize, _state: &S) -> ControlFlow<()> {
+        match step >= self.0 {
+            true => ControlFlow::Break(()),
+            false => ControlFlow::Continue(()),
+        }
+    }
+
+    fn observe(&mut self, _step: usize, _state: &S, _delta: &S::Delta) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// Ticks [`crate::progress`] after every step, stopping the run once the
+/// phase has run out of time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub task: &'static str,
+    pub total: Option<usize>,
+}
+
+impl<S: Stepper + ?Sized> Observer<S> for Progress {
+    fn observe(&mut self, step: usize, _state: &S, _delta: &S::Delta) -> ControlFlow<()> {
+        match crate::progress::tick(self.task, step + 1, self.total) {
+            true => ControlFlow::Continue(()),
+            false => ControlFlow::Break(()),
+        }
+    }
+}
+
+/// Steps until `stepper` is done or `observer` stops it. Returns the number
+/// of steps taken
+pub fn run<S, O>(stepper: &mut S, observer: &mut O) -> usize
+where
+    S: Stepper + ?Sized,
+    O: Observer<S> + ?Sized,
+{
+    let mut steps = 0;
+    while observer.before(steps, stepper).is_continue() {
+        let Some(delta) = stepper.step() else {
+            break;
+        };
+        steps += 1;
+        if observer.observe(steps - 1, stepper, &delta).is_break() {
+            break;
+        }
+    }
+    steps
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Counts down to zero, one step at a time
+    struct Countdown(u32);
+
+    impl Stepper for Countdown {
+        type Delta = u32;
+
+        fn step(&mut self) -> Option<u32> {
+            self.0 = self.0.checked_sub(1)?;
+            Some(self.0)
+        }
+
+        fn is_done(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let mut countdown = Countdown(5);
+        assert!(!countdown.is_done());
+        assert_eq!(5, run(&mut countdown, &mut ()));
+        assert!(countdown.is_done());
+        assert_eq!(0, run(&mut countdown, &mut ()));
+    }
+
+    #[test]
+    fn test_observers() {
+        let mut seen = [0; 5];
+        let mut record = |step: usize, state: &Countdown, delta: &u32| {
+            assert_eq!(state.0, *delta);
+            seen[step] = *delta;
+            ControlFlow::Continue(())
+        };
+        assert_eq!(3, run(&mut Countdown(5), &mut (Limit(3), &mut record)));
+        assert_eq!([4, 3, 2, 0, 0], seen);
+
+        let mut countdown = Countdown(5);
+        let mut unreachable = |_: usize, _: &Countdown, _: &u32| -> ControlFlow<()> {
+            panic!("Limit(0) took a step")
+        };
+        assert_eq!(0, run(&mut countdown, &mut (Limit(0), &mut unreachable)));
+        assert_eq!(5, countdown.0);
+        assert_eq!(5, run(&mut Countdown(5), &mut Limit(9)));
+
+        // outside of a phase progress never stops anything
+        let mut progress = Progress {
+            task: "countdown",
+            total: Some(5),
+        };
+        assert_eq!(5, run(&mut Countdown(5), &mut progress));
+    }
+}
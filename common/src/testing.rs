@@ -0,0 +1,21 @@
+//! Checks shared by the days' tests
+
+use core::fmt::Debug;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Checks that `value` comes back unchanged from both JSON and postcard
+pub fn assert_round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T) {
+    let json = serde_json::to_string(value).expect("Failed to write JSON");
+    let bytes = postcard::to_allocvec(value).expect("Failed to write postcard");
+    assert_eq!(
+        *value,
+        serde_json::from_str::<T>(&json).expect("Failed to read JSON"),
+        "JSON round trip"
+    );
+    assert_eq!(
+        *value,
+        postcard::from_bytes::<T>(&bytes).expect("Failed to read postcard"),
+        "postcard round trip"
+    );
+}
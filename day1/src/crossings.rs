@@ -0,0 +1,226 @@
+//! Counting the passes over any range of a rotation list, and trying out
+//! different rotations, without simulating from the start every time.
+//!
+//! Where the pointer is before a rotation only depends on how far the
+//! rotations before it turned the dial, so a segment tree can keep, for every
+//! run of rotations, how far they turn the dial and how often they pass the
+//! target from each position they could start at. Queries and replacements
+//! take logarithmic time, but every node holds a count per position, so this
+//! is only for dials with a modest number of positions
+
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use crate::{Count, Dial, Rotation};
+
+/// Rotations that turn the dial nowhere, which pad the tree out to a power
+/// of two
+const NOTHING: Rotation = Rotation::Right(0);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crossings {
+    dial: Dial,
+    rotations: Vec<Rotation>,
+    /// the number of leaves, a power of two
+    width: usize,
+    /// where each node leaves the pointer, relative to where it started
+    turns: Vec<usize>,
+    /// `size` passes per node, one for every position the node could start
+    /// from. Stops at `Count::MAX` like [`crate::Simulator::apply_multiple`]
+    passes: Vec<Count>,
+}
+
+impl Crossings {
+    pub fn new(dial: Dial, rotations: Vec<Rotation>) -> Self {
+        let width = rotations.len().next_power_of_two();
+        let size = dial.size() as usize;
+        let mut crossings = Self {
+            dial,
+            rotations,
+            width,
+            turns: vec![0; 2 * width],
+            passes: vec![0; 2 * width * size],
+        };
+        for leaf in 0..width {
+            crossings.fill_leaf(leaf);
+        }
+        for node in (1..width).rev() {
+            crossings.merge(node);
+        }
+        crossings
+    }
+
+    pub fn dial(&self) -> &Dial {
+        &self.dial
+    }
+
+    pub fn rotations(&self) -> &[Rotation] {
+        &self.rotations
+    }
+
+    pub fn len(&self) -> usize {
+        self.rotations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rotations.is_empty()
+    }
+
+    fn size(&self) -> usize {
+        self.dial.size() as usize
+    }
+
+    fn node_passes(&self, node: usize) -> &[Count] {
+        let size = self.size();
+        &self.passes[node * size..(node + 1) * size]
+    }
+
+    fn fill_leaf(&mut self, leaf: usize) {
+        let rotation = self.rotations.get(leaf).copied().unwrap_or(NOTHING);
+        let node = self.width + leaf;
+        let size = self.size();
+        for position in 0..size {
+            let (passes, _) = self.dial.rotate(position as i32, rotation);
+            self.passes[node * size + position] = passes;
+        }
+        self.turns[node] = self.dial.rotate(0, rotation).1 as usize;
+    }
+
+    /// Works out a node from its two children
+    fn merge(&mut self, node: usize) {
+        let size = self.size();
+        let (left, right) = (2 * node, 2 * node + 1);
+        let turn = self.turns[left];
+        for position in 0..size {
+            let passes = self.node_passes(left)[position]
+                .saturating_add(self.node_passes(right)[(position + turn) % size]);
+            self.passes[node * size + position] = passes;
+        }
+        self.turns[node] = (turn + self.turns[right]) % size;
+    }
+
+    /// How often rotations `range`, numbered from 0, pass the target when
+    /// every rotation is applied in turn from the dial's start
+    pub fn passes(&self, range: Range<usize>) -> Count {
+        assert!(
+            range.end <= self.len(),
+            "range {range:?} is past the end of {} rotations",
+            self.len()
+        );
+        let mut position = self.dial.start() as usize;
+        let mut passes = 0;
+        self.walk(1, 0..self.width, &range, &mut position, &mut passes);
+        passes
+    }
+
+    /// Moves the pointer through the part of `node` (which covers `covers`)
+    /// that comes before the end of `range`, counting the passes inside it
+    fn walk(
+        &self,
+        node: usize,
+        covers: Range<usize>,
+        range: &Range<usize>,
+        position: &mut usize,
+        passes: &mut Count,
+    ) {
+        if covers.start >= range.end {
+            return;
+        }
+        let whole = covers.end <= range.end;
+        if whole && covers.end <= range.start {
+            *position = (*position + self.turns[node]) % self.size();
+        } else if whole && covers.start >= range.start {
+            *passes = passes.saturating_add(self.node_passes(node)[*position]);
+            *position = (*position + self.turns[node]) % self.size();
+        } else {
+            let middle = (covers.start + covers.end) / 2;
+            self.walk(2 * node, covers.start..middle, range, position, passes);
+            self.walk(2 * node + 1, middle..covers.end, range, position, passes);
+        }
+    }
+
+    /// Swaps rotation `index` for `rotation`, returning the one it replaced
+    pub fn replace(&mut self, index: usize, rotation: Rotation) -> Rotation {
+        let replaced = core::mem::replace(&mut self.rotations[index], rotation);
+        self.fill_leaf(index);
+        let mut node = (self.width + index) / 2;
+        while node > 0 {
+            self.merge(node);
+            node /= 2;
+        }
+        replaced
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Clicks, State2};
+
+    /// Rotations that look random, but are the same every time
+    fn rotations(n: usize) -> Vec<Rotation> {
+        let mut seed: u64 = 0x2545f4914f6cdd1d;
+        (0..n)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                let clicks = (seed % 450) as Clicks;
+                match seed.is_multiple_of(2) {
+                    true => Rotation::Right(clicks),
+                    false => Rotation::Left(clicks),
+                }
+            })
+            .collect()
+    }
+
+    fn check(crossings: &Crossings) {
+        let rotations = crossings.rotations();
+        let prefixes = (0..=rotations.len())
+            .map(|n| {
+                State2::with_dial(*crossings.dial()).apply_multiple(rotations[..n].iter().copied())
+            })
+            .collect::<Vec<_>>();
+        for i in 0..=rotations.len() {
+            for j in i..=rotations.len() {
+                assert_eq!(
+                    prefixes[j] - prefixes[i],
+                    crossings.passes(i..j),
+                    "passes of rotations {i}..{j}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_passes() {
+        let example = crate::parse("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n")
+            .expect("Failed to parse");
+        let crossings = Crossings::new(Dial::default(), example);
+        assert_eq!(6, crossings.passes(0..10));
+        assert_eq!(3, crossings.passes(2..6));
+        assert_eq!(0, crossings.passes(3..3));
+        check(&crossings);
+
+        let marked = Dial::new(13, 4)
+            .and_then(|dial| dial.with_target(9))
+            .expect("valid dial");
+        check(&Crossings::new(marked, rotations(37)));
+        check(&Crossings::new(Dial::default(), Vec::new()));
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut crossings = Crossings::new(Dial::default(), rotations(20));
+        for (index, rotation) in [
+            (4, Rotation::Right(500)),
+            (0, Rotation::Left(-7)),
+            (19, Rotation::Left(1)),
+            (4, Rotation::Right(0)),
+        ] {
+            let replaced = crossings.rotations()[index];
+            assert_eq!(replaced, crossings.replace(index, rotation));
+            check(&crossings);
+        }
+    }
+}
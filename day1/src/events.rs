@@ -0,0 +1,315 @@
+//! What happens as a dial is turned, one event at a time, so that a disputed
+//! password can be traced back to the rotations that made it. A rotation has
+//! at most three events however far it turns, so the passwords can be counted
+//! from the events too
+
+use core::fmt::{self, Display, Write};
+
+use crate::{Count, Dial, Rotation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    /// Rotation number `step`, counting from 0, turned the pointer from
+    /// `before` to `after`. Comes before the rotation's other events
+    Turn {
+        step: usize,
+        rotation: Rotation,
+        before: i32,
+        after: i32,
+    },
+    /// Rotation `step` reached the target `count` times, the first time
+    /// `first_click` clicks in. These count for part 2 even if the rotation
+    /// stops on the target
+    Cross {
+        step: usize,
+        first_click: Count,
+        count: Count,
+    },
+    /// Rotation `step` left the pointer on the target, which counts for part 1
+    Land { step: usize },
+}
+
+impl Event {
+    pub fn step(&self) -> usize {
+        match *self {
+            Event::Turn { step, .. } | Event::Cross { step, .. } | Event::Land { step } => step,
+        }
+    }
+
+    pub fn is_crossing(&self) -> bool {
+        matches!(self, Event::Cross { .. })
+    }
+
+    pub fn is_landing(&self) -> bool {
+        matches!(self, Event::Land { .. })
+    }
+
+    /// How much the event adds to the part 1 password
+    pub fn landings(&self) -> Count {
+        self.is_landing().into()
+    }
+
+    /// How much the event adds to the part 2 password
+    pub fn passes(&self) -> Count {
+        match *self {
+            Event::Cross { count, .. } => count,
+            _ => 0,
+        }
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Turn {
+                step,
+                rotation,
+                before,
+                after,
+            } => write!(
+                f,
+                "rotation {} ({rotation}) turned the dial from {before} to {after}",
+                step + 1
+            ),
+            Event::Cross {
+                step,
+                first_click,
+                count: 1,
+            } => write!(
+                f,
+                "rotation {} reached the target after {first_click} clicks",
+                step + 1
+            ),
+            Event::Cross {
+                step,
+                first_click,
+                count,
+            } => write!(
+                f,
+                "rotation {} reached the target {count} times, first after {first_click} clicks",
+                step + 1
+            ),
+            Event::Land { step } => write!(f, "rotation {} stopped on the target", step + 1),
+        }
+    }
+}
+
+/// The events of turning a dial through some rotations, worked out as they're
+/// needed. Made by [`Dial::events`]
+#[derive(Debug, Clone)]
+pub struct Events<I> {
+    dial: Dial,
+    position: i32,
+    rotations: core::iter::Enumerate<I>,
+    current: Option<Current>,
+}
+
+/// The rotation whose crossing and landing haven't been reported yet
+#[derive(Debug, Clone, Copy)]
+struct Current {
+    step: usize,
+    cross: Option<Event>,
+    lands: bool,
+}
+
+impl Dial {
+    /// Turns the dial through `rotations` from its start, one event at a time
+    pub fn events<I: IntoIterator<Item = Rotation>>(&self, rotations: I) -> Events<I::IntoIter> {
+        Events {
+            dial: *self,
+            position: self.start(),
+            rotations: rotations.into_iter().enumerate(),
+            current: None,
+        }
+    }
+}
+
+impl<I: Iterator<Item = Rotation>> Iterator for Events<I> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        if let Some(current) = &mut self.current {
+            if let Some(cross) = current.cross.take() {
+                return Some(cross);
+            }
+            let Current { step, lands, .. } = *current;
+            self.current = None;
+            if lands {
+                return Some(Event::Land { step });
+            }
+        }
+        let (step, rotation) = self.rotations.next()?;
+        let before = self.position;
+        let (count, after) = self.dial.rotate(before, rotation);
+        self.position = after;
+        let cross = (count > 0).then(|| Event::Cross {
+            step,
+            // it's reached, so no further than the rotation's clicks, which fit
+            first_click: self.dial.ahead(before, rotation.turns_right()) as Count,
+            count,
+        });
+        self.current = Some(Current {
+            step,
+            cross,
+            lands: after == self.dial.target(),
+        });
+        Some(Event::Turn {
+            step,
+            rotation,
+            before,
+            after,
+        })
+    }
+}
+
+/// Writes the events as CSV, one row per event. Rotations are numbered from
+/// 1 so that they match the lines of the input
+pub fn write_csv(out: &mut impl Write, events: impl IntoIterator<Item = Event>) -> fmt::Result {
+    writeln!(out, "rotation,event,turn,before,after,first_click,count")?;
+    for event in events {
+        let rotation = event.step() + 1;
+        match event {
+            Event::Turn {
+                rotation: turn,
+                before,
+                after,
+                ..
+            } => writeln!(out, "{rotation},turn,{turn},{before},{after},,")?,
+            Event::Cross {
+                first_click, count, ..
+            } => writeln!(out, "{rotation},cross,,,,{first_click},{count}")?,
+            Event::Land { .. } => writeln!(out, "{rotation},land,,,,,")?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use super::*;
+    use crate::{Clicks, State1, State2};
+
+    const EXAMPLE_INPUT: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    #[test]
+    fn test_events() {
+        let events = Dial::default()
+            .events([Rotation::Right(250), Rotation::Left(5), Rotation::Left(0)])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Event::Turn {
+                    step: 0,
+                    rotation: Rotation::Right(250),
+                    before: 50,
+                    after: 0
+                },
+                Event::Cross {
+                    step: 0,
+                    first_click: 50,
+                    count: 3
+                },
+                Event::Land { step: 0 },
+                Event::Turn {
+                    step: 1,
+                    rotation: Rotation::Left(5),
+                    before: 0,
+                    after: 95
+                },
+                Event::Turn {
+                    step: 2,
+                    rotation: Rotation::Left(0),
+                    before: 95,
+                    after: 95
+                },
+            ],
+            events
+        );
+    }
+
+    #[test]
+    fn test_agrees_with_simulators() {
+        let dials = [
+            Dial::default(),
+            Dial::new(10, 5).expect("valid dial"),
+            Dial::new(7, 0)
+                .and_then(|dial| dial.with_target(3))
+                .expect("valid dial"),
+        ];
+        let rotations = crate::parse(EXAMPLE_INPUT)
+            .expect("Failed to parse")
+            .into_iter()
+            .chain([
+                Rotation::Left(0),
+                Rotation::Right(-30),
+                Rotation::Left(-13),
+                Rotation::Right(1000),
+            ])
+            .collect::<Vec<_>>();
+        for dial in dials {
+            for n in 0..=rotations.len() {
+                let rotations = &rotations[..n];
+                let events = dial.events(rotations.iter().copied()).collect::<Vec<_>>();
+                assert_eq!(
+                    State1::with_dial(dial).apply_multiple(rotations.iter().copied()),
+                    events.iter().map(Event::landings).sum::<Count>(),
+                    "landings on {dial:?} after {n} rotations"
+                );
+                assert_eq!(
+                    State2::with_dial(dial).apply_multiple(rotations.iter().copied()),
+                    events.iter().map(Event::passes).sum::<Count>(),
+                    "crossings on {dial:?} after {n} rotations"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_huge_rotations() {
+        let dial = Dial::default();
+        let huge = Rotation::Right(Clicks::MAX);
+        let events = dial.events([huge]).collect::<Vec<_>>();
+        assert_eq!(2, events.len());
+        let count = dial.rotate(50, huge).0;
+        assert_eq!(
+            Event::Cross {
+                step: 0,
+                first_click: 50,
+                count
+            },
+            events[1]
+        );
+        assert_eq!(
+            format!("rotation 1 reached the target {count} times, first after 50 clicks"),
+            events[1].to_string()
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut csv = String::new();
+        write_csv(
+            &mut csv,
+            Dial::default().events([Rotation::Left(68), Rotation::Right(18)]),
+        )
+        .expect("Failed to write CSV");
+        assert_eq!(
+            "rotation,event,turn,before,after,first_click,count
+1,turn,L68,50,82,,
+1,cross,,,,50,1
+2,turn,R18,82,0,,
+2,cross,,,,18,1
+2,land,,,,,
+",
+            csv
+        );
+    }
+}
@@ -0,0 +1,554 @@
+//! Working backwards from a password: the fewest rotations to change, drop or
+//! insert so that a list of rotations gives the password we want. Handy for
+//! making examples, and for finding out how far off a rejected answer is.
+//!
+//! Counts never go down, so for every number of edits, rotation and position
+//! this keeps the counts up to the target that the rest of the rotations
+//! could still add, then picks the edits by walking forwards through them.
+//! That's a lot of sets, so keep the edits and targets modest
+
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use core::fmt::{self, Display};
+
+use crate::{
+    Clicks, Count, Dial, Error, LandsOnAny, LandsOnTarget, PassesTarget, Policy, Rotation, Turn,
+};
+
+/// A [`Policy`] that [`edit_to`] can work backwards from. It has to count
+/// each turn on its own, without remembering the turns before it
+pub trait Invertible: Policy {
+    /// Whether adding a whole turn to a rotation counts once more
+    const COUNTS_TURNS: bool;
+}
+
+impl Invertible for LandsOnTarget {
+    const COUNTS_TURNS: bool = false;
+}
+
+impl Invertible for PassesTarget {
+    const COUNTS_TURNS: bool = true;
+}
+
+impl Invertible for LandsOnAny {
+    const COUNTS_TURNS: bool = false;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Edit {
+    /// Rotation `index`, counting from 0, was `from` and is now `to`
+    Change {
+        index: usize,
+        from: Rotation,
+        to: Rotation,
+    },
+    Drop {
+        index: usize,
+        rotation: Rotation,
+    },
+    /// `rotation` was added after the first `index` rotations
+    Insert {
+        index: usize,
+        rotation: Rotation,
+    },
+}
+
+impl Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Edit::Change { index, from, to } => {
+                write!(f, "changed rotation {} from {from} to {to}", index + 1)
+            }
+            Edit::Drop { index, rotation } => {
+                write!(f, "dropped rotation {} ({rotation})", index + 1)
+            }
+            Edit::Insert { index: 0, rotation } => write!(f, "inserted {rotation} at the start"),
+            Edit::Insert { index, rotation } => {
+                write!(f, "inserted {rotation} after rotation {index}")
+            }
+        }
+    }
+}
+
+/// Rotations that give the wanted password, and how they differ from the
+/// original ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edited {
+    pub rotations: Vec<Rotation>,
+    /// in the order of the rotations they affect
+    pub edits: Vec<Edit>,
+}
+
+impl Dial {
+    /// A rotation from `from` to `to` that passes the target as rarely as
+    /// possible, plus `turns` whole turns. `None` if that doesn't fit in
+    /// [`Clicks`]
+    pub fn detour(&self, from: i32, to: i32, turns: Count) -> Option<Rotation> {
+        let right = (to - from).rem_euclid(self.size);
+        let left = (self.size - right) % self.size;
+        // one way round or the other misses the target, unless the pointer
+        // ends up on it
+        let passes_right = right > 0 && self.ahead(from, true) <= right as u128;
+        let passes_left = left > 0 && self.ahead(from, false) <= left as u128;
+        let extra = Clicks::try_from(turns)
+            .ok()?
+            .checked_mul(self.size as Clicks)?;
+        match passes_right && !passes_left {
+            true => Some(Rotation::Left(extra.checked_add(left as Clicks)?)),
+            false => Some(Rotation::Right(extra.checked_add(right as Clicks)?)),
+        }
+    }
+}
+
+/// Counts from 0 up to the target, as sorted ranges that neither overlap nor
+/// touch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Counts(Vec<(Count, Count)>);
+
+impl Counts {
+    fn contains(&self, count: Count) -> bool {
+        self.0
+            .iter()
+            .any(|&(low, high)| (low..=high).contains(&count))
+    }
+
+    /// The largest count that's no more than `count`
+    fn at_most(&self, count: Count) -> Option<Count> {
+        self.0
+            .iter()
+            .rev()
+            .find_map(|&(low, high)| (low <= count).then(|| high.min(count)))
+    }
+}
+
+/// Collects ranges of counts, dropping anything past the target
+#[derive(Clone)]
+struct Collect {
+    target: Count,
+    ranges: Vec<(Count, Count)>,
+}
+
+impl Collect {
+    fn new(target: Count) -> Self {
+        Self {
+            target,
+            ranges: Vec::new(),
+        }
+    }
+
+    /// Adds `counts` plus `shift`, and if `turns` is set everything above
+    /// them too
+    fn add(&mut self, counts: &Counts, shift: Count, turns: bool) {
+        let mut ranges = counts.0.iter().filter_map(|&(low, high)| {
+            let low = low.checked_add(shift).filter(|&low| low <= self.target)?;
+            Some((low, high.saturating_add(shift).min(self.target)))
+        });
+        match turns {
+            true => self
+                .ranges
+                .extend(ranges.next().map(|(low, _)| (low, self.target))),
+            false => self.ranges.extend(ranges),
+        }
+    }
+
+    fn finish(mut self) -> Counts {
+        self.ranges.sort_unstable();
+        let mut merged: Vec<(Count, Count)> = Vec::with_capacity(self.ranges.len());
+        for (low, high) in self.ranges {
+            match merged.last_mut() {
+                Some(last) if low <= last.1.saturating_add(1) => last.1 = last.1.max(high),
+                _ => merged.push((low, high)),
+            }
+        }
+        Counts(merged)
+    }
+}
+
+/// Everything [`edit_to`] works out before it can start on the edits
+struct Solver<'a, P> {
+    dial: Dial,
+    policy: P,
+    rotations: &'a [Rotation],
+    target: Count,
+    /// what a rotation of our choosing counts at least, from each position
+    /// to each position
+    least: Vec<Count>,
+    /// positions whose rows of `least` are the same, so that they can share
+    /// the work
+    classes: Vec<Vec<usize>>,
+    /// `layers[k][i * size + p]` holds what rotations `i..` can count from
+    /// position `p` with up to `k` edits
+    layers: Vec<Vec<Counts>>,
+}
+
+impl<'a, P: Invertible> Solver<'a, P> {
+    fn new(dial: Dial, mut policy: P, rotations: &'a [Rotation], target: Count) -> Self {
+        let size = dial.size() as usize;
+        let mut least = Vec::with_capacity(size * size);
+        for from in 0..dial.size() {
+            for to in 0..dial.size() {
+                let rotation = dial.detour(from, to, 0).expect("less than a turn fits");
+                least.push(count(&dial, &mut policy, from, rotation).0);
+            }
+        }
+        let mut classes = BTreeMap::<&[Count], Vec<usize>>::new();
+        for (from, row) in least.chunks(size).enumerate() {
+            classes.entry(row).or_default().push(from);
+        }
+        let classes = classes.into_values().collect();
+        Self {
+            dial,
+            policy,
+            rotations,
+            target,
+            least,
+            classes,
+            layers: Vec::new(),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.dial.size() as usize
+    }
+
+    fn counts(&self, edits: usize, index: usize, position: i32) -> &Counts {
+        &self.layers[edits][index * self.size() + position as usize]
+    }
+
+    /// What a rotation of our choosing, followed by rotations `index..` with
+    /// `edits` edits, can count from each class of positions
+    fn chosen(&self, edits: usize, index: usize) -> Vec<Counts> {
+        let size = self.size();
+        self.classes
+            .iter()
+            .map(|class| {
+                let row = &self.least[class[0] * size..(class[0] + 1) * size];
+                let mut collect = Collect::new(self.target);
+                for (to, &least) in row.iter().enumerate() {
+                    collect.add(self.counts(edits, index, to as i32), least, P::COUNTS_TURNS);
+                }
+                collect.finish()
+            })
+            .collect()
+    }
+
+    /// Works out the counts for up to one more edit than the last layer
+    fn add_layer(&mut self) {
+        let (size, n) = (self.size(), self.rotations.len());
+        let edits = self.layers.len();
+        self.layers.push(vec![Counts::default(); (n + 1) * size]);
+        for index in (0..=n).rev() {
+            let (inserted, changed) = match edits.checked_sub(1) {
+                Some(fewer) => (
+                    self.chosen(fewer, index),
+                    match index < n {
+                        true => self.chosen(fewer, index + 1),
+                        false => Vec::new(),
+                    },
+                ),
+                None => (Vec::new(), Vec::new()),
+            };
+            let mut counts = vec![Collect::new(self.target); size];
+            for (class, positions) in self.classes.iter().enumerate() {
+                for &from in positions {
+                    for chosen in [&inserted, &changed] {
+                        if let Some(chosen) = chosen.get(class) {
+                            counts[from].add(chosen, 0, false);
+                        }
+                    }
+                }
+            }
+            for (from, collect) in counts.iter_mut().enumerate() {
+                let from = from as i32;
+                if let Some(fewer) = edits.checked_sub(1) {
+                    collect.add(self.counts(fewer, index, from), 0, false);
+                    if index < n {
+                        // dropping the rotation
+                        collect.add(self.counts(fewer, index + 1, from), 0, false);
+                    }
+                }
+                if index < n {
+                    let (counted, after) =
+                        count(&self.dial, &mut self.policy, from, self.rotations[index]);
+                    collect.add(self.counts(edits, index + 1, after), counted, false);
+                } else if edits == 0 {
+                    collect.ranges.push((0, 0));
+                }
+            }
+            for (from, collect) in counts.into_iter().enumerate() {
+                self.layers[edits][index * size + from] = collect.finish();
+            }
+        }
+    }
+
+    /// Whether a rotation of our choosing from `from` to `to`, then whatever
+    /// counts `then` holds, can make `wanted`. Returns the extra turns and
+    /// what's left for `then` to count
+    fn fits(&self, from: i32, to: i32, then: &Counts, wanted: Count) -> Option<(Count, Count)> {
+        let least = self.least[from as usize * self.size() + to as usize];
+        let rest = wanted.checked_sub(least)?;
+        match P::COUNTS_TURNS {
+            true => then.at_most(rest).map(|left| (rest - left, left)),
+            false => then.contains(rest).then_some((0, rest)),
+        }
+    }
+
+    /// Walks forwards through the layers, keeping rotations wherever the
+    /// edits left allow it
+    fn edits(&mut self) -> Result<Edited, Error> {
+        let mut edits = self.layers.len() - 1;
+        let (mut index, mut position, mut wanted) = (0, self.dial.start(), self.target);
+        let mut edited = Edited {
+            rotations: Vec::new(),
+            edits: Vec::new(),
+        };
+        let n = self.rotations.len();
+        'walk: while index < n || wanted > 0 {
+            if let Some(&rotation) = self.rotations.get(index) {
+                let (counted, after) = count(&self.dial, &mut self.policy, position, rotation);
+                if let Some(left) = wanted.checked_sub(counted)
+                    && self.counts(edits, index + 1, after).contains(left)
+                {
+                    edited.rotations.push(rotation);
+                    (index, position, wanted) = (index + 1, after, left);
+                    continue;
+                }
+            }
+            edits -= 1;
+            if let Some(&rotation) = self.rotations.get(index) {
+                if self.counts(edits, index + 1, position).contains(wanted) {
+                    edited.edits.push(Edit::Drop { index, rotation });
+                    index += 1;
+                    continue;
+                }
+                for to in 0..self.dial.size() {
+                    let then = self.counts(edits, index + 1, to);
+                    if let Some((turns, left)) = self.fits(position, to, then, wanted) {
+                        let to_rotation = self
+                            .dial
+                            .detour(position, to, turns)
+                            .ok_or(Error::Overflow)?;
+                        edited.rotations.push(to_rotation);
+                        edited.edits.push(Edit::Change {
+                            index,
+                            from: rotation,
+                            to: to_rotation,
+                        });
+                        (index, position, wanted) = (index + 1, to, left);
+                        continue 'walk;
+                    }
+                }
+            }
+            for to in 0..self.dial.size() {
+                if let Some((turns, left)) =
+                    self.fits(position, to, self.counts(edits, index, to), wanted)
+                {
+                    let rotation = self
+                        .dial
+                        .detour(position, to, turns)
+                        .ok_or(Error::Overflow)?;
+                    edited.rotations.push(rotation);
+                    edited.edits.push(Edit::Insert { index, rotation });
+                    (position, wanted) = (to, left);
+                    continue 'walk;
+                }
+            }
+            unreachable!("the layers promised {wanted} more from rotation {index}");
+        }
+        Ok(edited)
+    }
+}
+
+/// What `policy` counts for `rotation` from `position`, and where it leaves
+/// the pointer
+fn count(dial: &Dial, policy: &mut impl Policy, position: i32, rotation: Rotation) -> (Count, i32) {
+    let (passes, after) = dial.rotate(position, rotation);
+    let turn = Turn {
+        rotation,
+        before: position,
+        after,
+        passes,
+    };
+    (policy.count(dial, &turn), after)
+}
+
+/// The fewest edits to `rotations` that make `policy` count `target` as the
+/// dial is turned from its start, trying no more than `max_edits` edits.
+/// Fails if a rotation would need more clicks than a [`Clicks`] holds
+pub fn edit_to<P: Invertible>(
+    dial: &Dial,
+    policy: P,
+    rotations: &[Rotation],
+    target: Count,
+    max_edits: usize,
+) -> Result<Option<Edited>, Error> {
+    let mut solver = Solver::new(*dial, policy, rotations, target);
+    for _ in 0..=max_edits {
+        solver.add_layer();
+        if solver
+            .counts(solver.layers.len() - 1, 0, dial.start())
+            .contains(target)
+        {
+            return solver.edits().map(Some);
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    /// Checks that `edited` gives `target` with exactly `edits` edits
+    fn check<P: Invertible + Default>(
+        dial: &Dial,
+        rotations: &[Rotation],
+        target: Count,
+        edits: usize,
+    ) -> Edited {
+        let edited = edit_to(dial, P::default(), rotations, target, edits)
+            .expect("fits")
+            .expect("enough edits");
+        assert_eq!(edits, edited.edits.len(), "{:?}", edited.edits);
+        assert_eq!(
+            target,
+            crate::Simulator::<P>::with_dial(*dial)
+                .apply_multiple(edited.rotations.iter().copied())
+        );
+        if let Some(fewer) = edits.checked_sub(1) {
+            assert_eq!(
+                None,
+                edit_to(dial, P::default(), rotations, target, fewer).expect("fits")
+            );
+        }
+        edited
+    }
+
+    #[test]
+    fn test_detour() {
+        let dial = Dial::default();
+        assert_eq!(Some(Rotation::Right(30)), dial.detour(50, 80, 0));
+        assert_eq!(Some(Rotation::Left(70)), dial.detour(80, 10, 0));
+        assert_eq!(Some(Rotation::Right(20)), dial.detour(80, 0, 0));
+        assert_eq!(Some(Rotation::Right(0)), dial.detour(0, 0, 0));
+        assert_eq!(Some(Rotation::Right(230)), dial.detour(50, 80, 2));
+        assert_eq!(None, dial.detour(50, 80, Count::MAX));
+    }
+
+    #[test]
+    fn test_edit_example() {
+        let dial = Dial::default();
+        let rotations = crate::parse(EXAMPLE_INPUT).expect("Failed to parse");
+        let edited = check::<LandsOnTarget>(&dial, &rotations, 3, 0);
+        assert_eq!(rotations, edited.rotations);
+        check::<PassesTarget>(&dial, &rotations, 6, 0);
+
+        // a rotation can pass the target any number of times, but only land on
+        // it once
+        check::<LandsOnTarget>(&dial, &rotations, 6, 3);
+        let edited = check::<PassesTarget>(&dial, &rotations, 100, 1);
+        assert!(matches!(edited.edits[..], [Edit::Insert { index: 10, .. }]));
+
+        let edited = check::<LandsOnTarget>(&dial, &rotations, 0, 1);
+        assert_eq!(
+            "dropped rotation 3 (R48)",
+            alloc::format!("{}", edited.edits[0])
+        );
+        check::<PassesTarget>(&dial, &rotations, 2, 2);
+        check::<PassesTarget>(&dial, &rotations, 0, 3);
+
+        assert_eq!(
+            None,
+            edit_to(&dial, LandsOnTarget, &rotations, 9, 2).expect("fits")
+        );
+    }
+
+    /// Every list that's up to `edits` edits away from `rotations`, using
+    /// rotations of up to three turns
+    fn edited(dial: &Dial, rotations: Vec<Rotation>, edits: usize) -> Vec<Vec<Rotation>> {
+        let mut lists = vec![rotations];
+        for _ in 0..edits {
+            let mut more = Vec::new();
+            for list in &lists {
+                for clicks in 0..=3 * dial.size() as Clicks {
+                    for rotation in [Rotation::Left(clicks), Rotation::Right(clicks)] {
+                        for index in 0..=list.len() {
+                            let mut inserted = list.clone();
+                            inserted.insert(index, rotation);
+                            more.push(inserted);
+                            if index < list.len() {
+                                let mut changed = list.clone();
+                                changed[index] = rotation;
+                                more.push(changed);
+                            }
+                        }
+                    }
+                }
+                for index in 0..list.len() {
+                    let mut dropped = list.clone();
+                    dropped.remove(index);
+                    more.push(dropped);
+                }
+            }
+            lists.extend(more);
+        }
+        lists
+    }
+
+    fn compare<P: Invertible + Default>(
+        dial: &Dial,
+        rotations: &[Rotation],
+        lists: &[Vec<Vec<Rotation>>],
+        target: Count,
+    ) {
+        let expected = lists.iter().position(|lists| {
+            lists.iter().any(|list| {
+                crate::Simulator::<P>::with_dial(*dial).apply_multiple(list.iter().copied())
+                    == target
+            })
+        });
+        let fewest = edit_to(dial, P::default(), rotations, target, lists.len() - 1)
+            .expect("fits")
+            .map(|edited| edited.edits.len());
+        assert_eq!(expected, fewest, "edits to reach {target}");
+    }
+
+    #[test]
+    fn test_edit_small_dial() {
+        let dial = Dial::new(5, 2)
+            .and_then(|dial| dial.with_target(1))
+            .expect("valid dial");
+        let rotations = vec![
+            Rotation::Left(1),
+            Rotation::Right(5),
+            Rotation::Left(7),
+            Rotation::Right(3),
+        ];
+        // compare with trying every list that's up to two edits away
+        let lists = [
+            vec![rotations.clone()],
+            edited(&dial, rotations.clone(), 1),
+            edited(&dial, rotations.clone(), 2),
+        ];
+        for target in 0..8 {
+            compare::<LandsOnTarget>(&dial, &rotations, &lists, target);
+            compare::<PassesTarget>(&dial, &rotations, &lists, target);
+        }
+        check::<LandsOnTarget>(&dial, &rotations, 5, 3);
+        check::<PassesTarget>(&dial, &rotations, 0, 3);
+        let edited = check::<PassesTarget>(&dial, &[], 3, 1);
+        assert_eq!(
+            vec![Edit::Insert {
+                index: 0,
+                rotation: Rotation::Right(18)
+            }],
+            edited.edits
+        );
+        assert_eq!(
+            "inserted R18 at the start",
+            alloc::format!("{}", edited.edits[0])
+        );
+    }
+}
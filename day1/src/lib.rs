@@ -0,0 +1,870 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{num::ParseIntError, ops::ControlFlow, str::FromStr};
+
+use common::{
+    lint::{LineLint, Problem},
+    stepper::{self, Progress, Stepper},
+};
+use tracing::info;
+
+mod crossings;
+mod events;
+mod inverse;
+
+pub use crossings::Crossings;
+pub use events::{Event, Events, write_csv};
+pub use inverse::{Edit, Edited, Invertible, edit_to};
+
+/// How far a rotation turns the dial
+#[cfg(not(feature = "i128"))]
+pub type Clicks = i64;
+#[cfg(feature = "i128")]
+pub type Clicks = i128;
+
+/// How often the pointer reached the target. Big enough to count the
+/// crossings of any single rotation
+#[cfg(not(feature = "i128"))]
+pub type Count = u64;
+#[cfg(feature = "i128")]
+pub type Count = u128;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Dial size must be positive, got {0}")]
+    InvalidSize(i32),
+    #[error("Position {position} is not on a dial of size {size}")]
+    PositionOffDial { position: i32, size: i32 },
+    #[error("Invalid rotation on line {line}")]
+    InvalidRotation {
+        line: usize,
+        #[source]
+        source: RotationError,
+    },
+    #[error("The password doesn't fit in {} bits", Count::BITS)]
+    Overflow,
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    Read(#[from] std::io::Error),
+}
+
+/// Why a single line isn't a rotation
+#[derive(Debug, thiserror::Error)]
+pub enum RotationError {
+    #[error("Empty line")]
+    Empty,
+    #[error("Unknown direction '{0}', expected 'L' or 'R'")]
+    UnknownDirection(char),
+    #[error("Invalid distance '{distance}'")]
+    InvalidDistance {
+        distance: String,
+        #[source]
+        source: ParseIntError,
+    },
+}
+
+/// A safe's dial, with `size` positions numbered from 0 and the pointer
+/// starting at `start`. The password counts how often the pointer reaches the
+/// `target` mark. The puzzle's dial is the default, normally adjusted by the
+/// `[day1]` settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dial {
+    size: i32,
+    start: i32,
+    target: i32,
+}
+
+impl Default for Dial {
+    fn default() -> Self {
+        Self {
+            size: 100,
+            start: 50,
+            target: 0,
+        }
+    }
+}
+
+impl Dial {
+    /// A dial with its target at 0
+    pub fn new(size: i32, start: i32) -> Result<Self, Error> {
+        if size <= 0 {
+            return Err(Error::InvalidSize(size));
+        }
+        let dial = Self {
+            size,
+            start,
+            target: 0,
+        };
+        dial.check(start)?;
+        Ok(dial)
+    }
+
+    /// Counts the pointer reaching `target` instead of 0
+    pub fn with_target(self, target: i32) -> Result<Self, Error> {
+        self.check(target)?;
+        Ok(Self { target, ..self })
+    }
+
+    fn check(&self, position: i32) -> Result<(), Error> {
+        match (0..self.size).contains(&position) {
+            true => Ok(()),
+            false => Err(Error::PositionOffDial {
+                position,
+                size: self.size,
+            }),
+        }
+    }
+
+    pub fn size(&self) -> i32 {
+        self.size
+    }
+
+    pub fn start(&self) -> i32 {
+        self.start
+    }
+
+    pub fn target(&self) -> i32 {
+        self.target
+    }
+
+    /// How many clicks it takes to first bring the pointer from `position`
+    /// to the target, turning right or left. A whole turn if it's already
+    /// there
+    pub(crate) fn ahead(&self, position: i32, right: bool) -> u128 {
+        let size = i64::from(self.size);
+        let from = (i64::from(position) - i64::from(self.target)).rem_euclid(size);
+        let ahead = match right {
+            true => size - from,
+            false if from == 0 => size,
+            false => from,
+        };
+        ahead as u128
+    }
+
+    /// Turns the pointer from `position`, returning how often it passed or
+    /// landed on the target and where it ended up
+    #[tracing::instrument(level = "debug", ret)]
+    pub fn rotate(&self, position: i32, rotation: Rotation) -> (Count, i32) {
+        // no rotation is more than 2^127 clicks, so u128 leaves plenty of room
+        let size = self.size as u128;
+        let clicks = rotation.clicks();
+        let right = rotation.turns_right();
+        let passes = match clicks.checked_sub(self.ahead(position, right)) {
+            Some(rest) => 1 + rest / size,
+            None => 0,
+        };
+        let from = i64::from(position).rem_euclid(i64::from(self.size)) as u128;
+        let to = match right {
+            true => (from + clicks % size) % size,
+            false => (from + size - clicks % size) % size,
+        };
+        let passes =
+            Count::try_from(passes).expect("a rotation passes the target at most once per click");
+        (passes, to as i32)
+    }
+}
+
+#[cfg(test)]
+#[rstest::rstest]
+#[case(50, Rotation::Right(50), (1, 0))]
+#[case(50, Rotation::Left(60), (1, 90))]
+#[case(1, Rotation::Left(8), (1, 93))]
+#[case(50, Rotation::Left(50), (1, 0))]
+#[case(50, Rotation::Right(250), (3, 0))]
+#[case(82, Rotation::Left(682), (7, 0))]
+fn test_rotate(#[case] start: i32, #[case] rotation: Rotation, #[case] res: (Count, i32)) {
+    assert_eq!(Dial::default().rotate(start, rotation), res);
+}
+
+#[test]
+fn test_dial() {
+    // a 10 position dial with the mark at 3 is the default dial shifted along
+    let dial = Dial::new(10, 5)
+        .and_then(|dial| dial.with_target(3))
+        .expect("valid dial");
+    assert_eq!(5, dial.start());
+    assert_eq!((0, 7), dial.rotate(5, Rotation::Right(2)));
+    assert_eq!((1, 3), dial.rotate(5, Rotation::Left(2)));
+    assert_eq!((2, 2), dial.rotate(5, Rotation::Left(13)));
+    assert_eq!((0, 4), dial.rotate(3, Rotation::Right(1)));
+    assert_eq!((3, 3), dial.rotate(3, Rotation::Right(30)));
+    assert!(matches!(
+        Dial::new(10, 10),
+        Err(Error::PositionOffDial {
+            position: 10,
+            size: 10
+        })
+    ));
+    assert!(matches!(
+        Dial::default().with_target(-1),
+        Err(Error::PositionOffDial { position: -1, .. })
+    ));
+    assert!(matches!(Dial::new(0, 0), Err(Error::InvalidSize(0))));
+}
+
+#[test]
+fn test_steps_part2() {
+    let mut s = State2::new(50);
+    assert_eq!((1, 82), Dial::default().rotate(s.0, Rotation::Left(68)));
+    s.apply(Rotation::Left(68));
+    assert_eq!((0, 52), Dial::default().rotate(s.0, Rotation::Left(30)));
+    s.apply(Rotation::Left(30));
+    assert_eq!((1, 0), Dial::default().rotate(s.0, Rotation::Right(48)));
+    s.apply(Rotation::Right(48));
+    assert_eq!((0, 95), Dial::default().rotate(s.0, Rotation::Left(5)));
+    s.apply(Rotation::Left(5));
+    assert_eq!((1, 55), Dial::default().rotate(s.0, Rotation::Right(60)));
+    s.apply(Rotation::Right(60));
+    assert_eq!((1, 0), Dial::default().rotate(s.0, Rotation::Left(55)));
+    s.apply(Rotation::Left(55));
+    assert_eq!((0, 99), Dial::default().rotate(s.0, Rotation::Left(1)));
+    s.apply(Rotation::Left(1));
+    assert_eq!((1, 0), Dial::default().rotate(s.0, Rotation::Left(99)));
+    s.apply(Rotation::Left(99));
+    assert_eq!((0, 14), Dial::default().rotate(s.0, Rotation::Right(14)));
+    s.apply(Rotation::Right(14));
+    assert_eq!((1, 32), Dial::default().rotate(s.0, Rotation::Left(82)));
+    s.apply(Rotation::Left(82));
+    assert_eq!((5, 32), Dial::default().rotate(s.0, Rotation::Right(500)));
+    s.apply(Rotation::Right(500));
+    assert_eq!((5, 32), Dial::default().rotate(s.0, Rotation::Left(500)));
+    s.apply(Rotation::Left(500));
+}
+#[test]
+fn test_example_part2() {
+    let mut s = State2::new(50);
+    let rotations = [
+        Rotation::Left(68),
+        Rotation::Left(30),
+        Rotation::Right(48),
+        Rotation::Left(5),
+        Rotation::Right(60),
+        Rotation::Left(55),
+        Rotation::Left(1),
+        Rotation::Left(99),
+        Rotation::Right(14),
+        Rotation::Left(82),
+    ];
+    let zeros = s.apply_multiple(rotations.into_iter());
+    assert_eq!(zeros, 6);
+}
+
+/// A single rotation of the dial, as seen by a [`Policy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Turn {
+    pub rotation: Rotation,
+    pub before: i32,
+    pub after: i32,
+    /// how often the pointer passed or landed on the target
+    pub passes: Count,
+}
+
+/// Decides what the password counts as a [`Simulator`] turns the dial
+pub trait Policy {
+    /// How much a single turn adds to the password
+    fn count(&mut self, dial: &Dial, turn: &Turn) -> Count;
+}
+
+impl<P: Policy + ?Sized> Policy for &mut P {
+    fn count(&mut self, dial: &Dial, turn: &Turn) -> Count {
+        (**self).count(dial, turn)
+    }
+}
+
+/// Counts the turns that leave the pointer on the target, for part 1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LandsOnTarget;
+
+impl Policy for LandsOnTarget {
+    fn count(&mut self, dial: &Dial, turn: &Turn) -> Count {
+        (turn.after == dial.target()).into()
+    }
+}
+
+/// Counts every time the pointer passes or lands on the target, for part 2
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PassesTarget;
+
+impl Policy for PassesTarget {
+    fn count(&mut self, _dial: &Dial, turn: &Turn) -> Count {
+        turn.passes
+    }
+}
+
+/// Counts the turns that leave the pointer on any of these positions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LandsOnAny(pub BTreeSet<i32>);
+
+impl Policy for LandsOnAny {
+    fn count(&mut self, _dial: &Dial, turn: &Turn) -> Count {
+        self.0.contains(&turn.after).into()
+    }
+}
+
+/// Counts the turns that go the other way to the one before
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DirectionChanges {
+    /// whether the last turn was to the left
+    last_left: Option<bool>,
+}
+
+impl Policy for DirectionChanges {
+    fn count(&mut self, _dial: &Dial, turn: &Turn) -> Count {
+        let left = matches!(turn.rotation, Rotation::Left(_));
+        let last_left = self.last_left.replace(left);
+        last_left.is_some_and(|last_left| last_left != left).into()
+    }
+}
+
+/// Turns a dial, adding up whatever the policy `P` counts
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Simulator<P>(pub i32, Dial, P);
+
+pub type State1 = Simulator<LandsOnTarget>;
+pub type State2 = Simulator<PassesTarget>;
+
+impl<P: Policy + Default> Simulator<P> {
+    /// Starts at `position` on the default dial
+    pub fn new(position: i32) -> Self {
+        Self(position, Dial::default(), P::default())
+    }
+
+    pub fn with_dial(dial: Dial) -> Self {
+        Self::with_policy(dial, P::default())
+    }
+}
+
+impl<P: Policy> Simulator<P> {
+    pub fn with_policy(dial: Dial, policy: P) -> Self {
+        Self(dial.start, dial, policy)
+    }
+
+    pub fn policy(&self) -> &P {
+        &self.2
+    }
+
+    pub fn apply(&mut self, rotation: Rotation) -> Count {
+        let (passes, after) = self.1.rotate(self.0, rotation);
+        let turn = Turn {
+            rotation,
+            before: self.0,
+            after,
+            passes,
+        };
+        self.0 = after;
+        self.2.count(&self.1, &turn)
+    }
+
+    /// Applies every rotation, stopping the total at [`Count::MAX`]
+    pub fn apply_multiple<I: Iterator<Item = Rotation>>(&mut self, iter: I) -> Count {
+        self.try_apply_multiple(iter).unwrap_or(Count::MAX)
+    }
+
+    /// Applies every rotation, failing if the total doesn't fit in a
+    /// [`Count`]
+    pub fn try_apply_multiple<I: Iterator<Item = Rotation>>(
+        &mut self,
+        iter: I,
+    ) -> Result<Count, Error> {
+        let mut total = Some(0);
+        let mut before = self.0;
+        let progress = Progress {
+            task: "rotations",
+            total: iter.size_hint().1,
+        };
+        let mut explain = |i: usize, rotating: &Rotating<Self, I>, counted: &Count| {
+            total = total.and_then(|total: Count| total.checked_add(*counted));
+            if *counted > 0
+                && let Some(total) = total
+            {
+                info!(
+                    target: "explain",
+                    "rotation {} ({:?}) turned the dial from {before} to {}, counting {counted} ({total} so far)",
+                    i + 1,
+                    rotating.last().expect("a rotation was applied"),
+                    rotating.state().0
+                );
+            }
+            before = rotating.state().0;
+            ControlFlow::Continue(())
+        };
+        stepper::run(
+            &mut Rotating::new(self, iter),
+            &mut (progress, &mut explain),
+        );
+        total.ok_or(Error::Overflow)
+    }
+}
+
+/// Turns a [`Simulator`]'s dial through a sequence of rotations, one rotation
+/// per step
+pub struct Rotating<'a, S, I> {
+    state: &'a mut S,
+    rotations: I,
+    next: Option<Rotation>,
+    last: Option<Rotation>,
+}
+
+impl<'a, S, I: Iterator<Item = Rotation>> Rotating<'a, S, I> {
+    pub fn new(state: &'a mut S, rotations: impl IntoIterator<IntoIter = I>) -> Self {
+        let mut rotations = rotations.into_iter();
+        Self {
+            state,
+            next: rotations.next(),
+            rotations,
+            last: None,
+        }
+    }
+
+    pub fn state(&self) -> &S {
+        self.state
+    }
+
+    /// the rotation applied by the latest step
+    pub fn last(&self) -> Option<Rotation> {
+        self.last
+    }
+}
+
+/// The delta is what the rotation added to the password
+impl<P: Policy, I: Iterator<Item = Rotation>> Stepper for Rotating<'_, Simulator<P>, I> {
+    type Delta = Count;
+
+    fn step(&mut self) -> Option<Count> {
+        let rotation = self.next?;
+        self.next = self.rotations.next();
+        self.last = Some(rotation);
+        Some(self.state.apply(rotation))
+    }
+
+    fn is_done(&self) -> bool {
+        self.next.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rotation {
+    Left(Clicks),
+    Right(Clicks),
+}
+
+#[test]
+fn test_steps_part1() {
+    let mut s = State1::new(50);
+    s.apply(Rotation::Left(68));
+    assert_eq!(s.0, 82);
+    s.apply(Rotation::Left(30));
+    assert_eq!(s.0, 52);
+    s.apply(Rotation::Right(48));
+    assert_eq!(s.0, 0);
+    s.apply(Rotation::Left(5));
+    assert_eq!(s.0, 95);
+    s.apply(Rotation::Right(60));
+    assert_eq!(s.0, 55);
+    s.apply(Rotation::Left(55));
+    assert_eq!(s.0, 0);
+    s.apply(Rotation::Left(1));
+    assert_eq!(s.0, 99);
+    s.apply(Rotation::Left(99));
+    assert_eq!(s.0, 0);
+    s.apply(Rotation::Right(14));
+    assert_eq!(s.0, 14);
+    s.apply(Rotation::Left(82));
+    assert_eq!(s.0, 32);
+}
+
+#[test]
+fn test_multiple_part1() {
+    let mut s = State1::new(50);
+    let rotations = [
+        Rotation::Left(68),
+        Rotation::Left(30),
+        Rotation::Right(48),
+        Rotation::Left(5),
+        Rotation::Right(60),
+        Rotation::Left(55),
+        Rotation::Left(1),
+        Rotation::Left(99),
+        Rotation::Right(14),
+        Rotation::Left(82),
+    ];
+    let zeros = s.apply_multiple(rotations.into_iter());
+    assert_eq!(zeros, 3);
+}
+
+#[test]
+fn test_rotating() {
+    let rotations = [Rotation::Left(68), Rotation::Right(48), Rotation::Left(500)];
+    let mut state = State2::new(50);
+    let mut rotating = Rotating::new(&mut state, rotations);
+    assert!(!rotating.is_done());
+    assert_eq!(Some(1), rotating.step());
+    assert_eq!(Some(Rotation::Left(68)), rotating.last());
+    assert_eq!(82, rotating.state().0);
+
+    let mut passes = Vec::new();
+    let mut record = |_: usize, _: &Rotating<State2, _>, crossings: &Count| {
+        passes.push(*crossings);
+        ControlFlow::Continue(())
+    };
+    assert_eq!(2, stepper::run(&mut rotating, &mut record));
+    assert!(rotating.is_done());
+    assert_eq!(None, rotating.step());
+    assert_eq!(vec![1, 5], passes);
+    assert_eq!(30, state.0);
+
+    let mut state = State1::new(50);
+    let mut rotating = Rotating::new(&mut state, rotations);
+    assert_eq!(1, stepper::run(&mut rotating, &mut stepper::Limit(1)));
+    assert_eq!(Some(0), rotating.step());
+    assert_eq!(30, rotating.state().0);
+}
+
+#[test]
+fn test_huge_rotations() {
+    let dial = Dial::default();
+    assert_eq!(
+        (92233720368547758, 57),
+        dial.rotate(50, Rotation::Right(i64::MAX as Clicks))
+    );
+    assert_eq!(
+        (92233720368547758, 58),
+        dial.rotate(50, Rotation::Left(i64::MIN as Clicks))
+    );
+
+    // every click is a whole turn, so the passes add up to exactly Count::MAX
+    let tiny = Dial::new(1, 0).expect("valid dial");
+    let huge = [Rotation::Right(Clicks::MAX), Rotation::Left(Clicks::MIN)];
+    assert_eq!(
+        Count::MAX,
+        State2::with_dial(tiny)
+            .try_apply_multiple(huge.into_iter())
+            .expect("fits exactly")
+    );
+    let too_many = huge.into_iter().chain([Rotation::Right(1)]);
+    assert!(matches!(
+        State2::with_dial(tiny).try_apply_multiple(too_many.clone()),
+        Err(Error::Overflow)
+    ));
+    assert_eq!(Count::MAX, State2::with_dial(tiny).apply_multiple(too_many));
+    let input = format!("R{}\nL{}\nR1\n", Clicks::MAX, Clicks::MIN);
+    assert!(matches!(part2_with(&input, tiny), Err(Error::Overflow)));
+}
+
+#[test]
+fn test_policies() {
+    /// counts the long rotations
+    struct Long;
+
+    impl Policy for Long {
+        fn count(&mut self, _dial: &Dial, turn: &Turn) -> Count {
+            match turn.rotation {
+                Rotation::Left(n) | Rotation::Right(n) => (n > 50).into(),
+            }
+        }
+    }
+
+    let rotations =
+        parse("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n").expect("Failed to parse");
+    let count = |policy: &mut dyn Policy| {
+        let mut simulator = Simulator::with_policy(Dial::default(), policy);
+        simulator.apply_multiple(rotations.iter().copied())
+    };
+    assert_eq!(3, count(&mut LandsOnTarget));
+    assert_eq!(6, count(&mut PassesTarget));
+    assert_eq!(4, count(&mut LandsOnAny(BTreeSet::from([0, 32]))));
+    assert_eq!(6, count(&mut DirectionChanges::default()));
+    assert_eq!(5, count(&mut Long));
+}
+
+impl Rotation {
+    /// whether the pointer moves to higher numbers. Negative distances turn
+    /// it the other way
+    pub fn turns_right(&self) -> bool {
+        match *self {
+            Rotation::Left(n) => n < 0,
+            Rotation::Right(n) => n >= 0,
+        }
+    }
+
+    /// how far the pointer moves, whichever way that is
+    // already a u128 with the i128 feature
+    #[allow(clippy::unnecessary_cast)]
+    pub fn clicks(&self) -> u128 {
+        match *self {
+            Rotation::Left(n) | Rotation::Right(n) => n.unsigned_abs() as u128,
+        }
+    }
+}
+
+/// The way rotations are written in the input, e.g. `L68`
+impl core::fmt::Display for Rotation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Rotation::Left(n) => write!(f, "L{n}"),
+            Rotation::Right(n) => write!(f, "R{n}"),
+        }
+    }
+}
+
+impl FromStr for Rotation {
+    type Err = RotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let dir = chars.next().ok_or(RotationError::Empty)?;
+        let distance = chars.as_str();
+        let num = distance
+            .parse::<Clicks>()
+            .map_err(|source| RotationError::InvalidDistance {
+                distance: distance.to_string(),
+                source,
+            })?;
+        match dir {
+            'L' => Ok(Self::Left(num)),
+            'R' => Ok(Self::Right(num)),
+            _ => Err(RotationError::UnknownDirection(dir)),
+        }
+    }
+}
+
+common::declare_build_id!();
+
+/// Checks every line of the input, reporting all problems at once
+pub fn lint(input: &str) -> Vec<Problem> {
+    Lint::lint(input)
+}
+
+/// [`lint`] a line at a time, for streamed input
+#[derive(Debug, Default)]
+pub struct Lint(Vec<Problem>);
+
+impl LineLint for Lint {
+    fn line(&mut self, number: usize, line: &str) {
+        let mut chars = line.chars();
+        let Some(dir) = chars.next() else {
+            self.0.push(Problem::new(number, "empty line"));
+            return;
+        };
+        if dir != 'L' && dir != 'R' {
+            self.0.push(Problem::at(
+                number,
+                1,
+                format!("unknown direction '{dir}', expected 'L' or 'R'"),
+            ));
+        }
+        let distance = chars.as_str();
+        if distance.parse::<Clicks>().is_err() {
+            self.0.push(Problem::at(
+                number,
+                2,
+                format!("invalid distance '{distance}'"),
+            ));
+        }
+    }
+
+    fn finish(self) -> Vec<Problem> {
+        self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let mut state1 = State1::new(50);
+    state1.apply_multiple([Rotation::Left(68), Rotation::Right(48)].into_iter());
+    let mut state2 = State2::with_dial(Dial::new(10, 5).expect("valid dial"));
+    state2.apply(Rotation::Right(23));
+    common::testing::assert_round_trip(&state1);
+    common::testing::assert_round_trip(&state2);
+    common::testing::assert_round_trip(&Rotation::Left(68));
+}
+
+#[test]
+fn test_lint() {
+    assert_eq!(Vec::<Problem>::new(), lint("L68\nR5\n"));
+    let problems = lint("L68\nX30\n\nRx\nU\n");
+    assert_eq!(
+        vec![
+            "line 2, column 1: unknown direction 'X', expected 'L' or 'R'",
+            "line 3: empty line",
+            "line 4, column 2: invalid distance 'x'",
+            "line 5, column 1: unknown direction 'U', expected 'L' or 'R'",
+            "line 5, column 2: invalid distance ''",
+        ],
+        problems.iter().map(|p| p.to_string()).collect::<Vec<_>>()
+    );
+}
+
+fn parse_line(i: usize, line: &str) -> Result<Rotation, Error> {
+    line.parse().map_err(|source| Error::InvalidRotation {
+        line: i + 1,
+        source,
+    })
+}
+
+pub fn parse(input: &str) -> Result<Vec<Rotation>, Error> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_line(i, line))
+        .collect()
+}
+
+/// Solves both parts in a single pass, so the rotations never have to be in
+/// memory at the same time. Returns the part 1 and part 2 answers
+#[cfg(feature = "std")]
+pub fn solve_lines<S: AsRef<str>>(
+    lines: impl IntoIterator<Item = std::io::Result<S>>,
+    dial: Dial,
+) -> Result<(Count, Count), Error> {
+    let mut landings = State1::with_dial(dial);
+    let mut passes = State2::with_dial(dial);
+    let (mut part1, mut part2) = (0, 0);
+    for (i, line) in lines.into_iter().enumerate() {
+        if !common::progress::tick("rotations", i, None) {
+            break;
+        }
+        let rotation = parse_line(i, line?.as_ref())?;
+        part1 = landings
+            .apply(rotation)
+            .checked_add(part1)
+            .ok_or(Error::Overflow)?;
+        part2 = passes
+            .apply(rotation)
+            .checked_add(part2)
+            .ok_or(Error::Overflow)?;
+    }
+    Ok((part1, part2))
+}
+
+pub fn part1(input: &str) -> Result<String, Error> {
+    part1_with(input, Dial::default())
+}
+
+pub fn part2(input: &str) -> Result<String, Error> {
+    part2_with(input, Dial::default())
+}
+
+pub fn part1_with(input: &str, dial: Dial) -> Result<String, Error> {
+    Ok(count_events(dial, parse(input)?, Event::landings)?.to_string())
+}
+
+pub fn part2_with(input: &str, dial: Dial) -> Result<String, Error> {
+    Ok(count_events(dial, parse(input)?, Event::passes)?.to_string())
+}
+
+/// Adds up what the events add to a password, explaining each one
+fn count_events(
+    dial: Dial,
+    rotations: Vec<Rotation>,
+    counts: fn(&Event) -> Count,
+) -> Result<Count, Error> {
+    let total = rotations.len();
+    let mut count: Count = 0;
+    for event in dial.events(rotations) {
+        if let Event::Turn { step, .. } = event
+            && !common::progress::tick("rotations", step, Some(total))
+        {
+            break;
+        }
+        let adds = counts(&event);
+        if adds > 0 {
+            count = count.checked_add(adds).ok_or(Error::Overflow)?;
+            info!(target: "explain", "{event} ({count} so far)");
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(feature = "plugin")]
+common::declare_plugin! {
+    day: 1,
+    parsed: Vec<Rotation>,
+    parse: |input| Ok(parse(input)?),
+    part1: |rotations: &Vec<Rotation>| {
+        let mut state = State1::with_dial(Dial::default());
+        Ok(state.apply_multiple(rotations.iter().copied()).to_string())
+    },
+    part2: |rotations: &Vec<Rotation>| {
+        let mut state = State2::with_dial(Dial::default());
+        Ok(state.apply_multiple(rotations.iter().copied()).to_string())
+    },
+}
+
+#[test]
+fn test_example_input() {
+    let input = "L68
+L30
+R48
+L5
+R60
+L55
+L1
+L99
+R14
+L82
+";
+    assert_eq!("3", part1(input).expect("part 1 failed"));
+    assert_eq!("6", part2(input).expect("part 2 failed"));
+    assert!(matches!(
+        parse("L68\nX30\n"),
+        Err(Error::InvalidRotation {
+            line: 2,
+            source: RotationError::UnknownDirection('X')
+        })
+    ));
+    assert!(matches!(
+        parse("L1x\n"),
+        Err(Error::InvalidRotation {
+            source: RotationError::InvalidDistance { .. },
+            ..
+        })
+    ));
+
+    let small = Dial::new(10, 5).expect("valid dial");
+    assert_eq!(
+        "1",
+        part1_with("R3\nR4\nL2\n", small).expect("part 1 failed")
+    );
+    assert_eq!(
+        "2",
+        part2_with("R3\nR4\nL2\n", small).expect("part 2 failed")
+    );
+    let marked = small.with_target(3).expect("valid target");
+    assert_eq!(
+        "1",
+        part1_with("R3\nR5\nL2\n", marked).expect("part 1 failed")
+    );
+    assert_eq!(
+        "1",
+        part2_with("R3\nR5\nL2\n", marked).expect("part 2 failed")
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_solve_lines() {
+    let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+    assert_eq!(
+        (3, 6),
+        solve_lines(input.lines().map(Ok), Dial::default()).expect("Failed to solve")
+    );
+    let err = solve_lines(["L1", "X2"].map(Ok), Dial::default()).expect_err("should fail");
+    assert!(err.to_string().contains("line 2"));
+}
@@ -0,0 +1,330 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::{format, vec::Vec};
+use core::{fmt::Display, str::FromStr};
+
+use common::lint::{LineLint, Problem};
+use tracing::{debug, info, instrument, warn};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid bank on line {line}")]
+    InvalidBank {
+        line: usize,
+        #[source]
+        source: NotADigit,
+    },
+    #[error("Bank on line {line} has {batteries} batteries, at least {PART2_BATTERIES} are needed")]
+    ShortBank { line: usize, batteries: usize },
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    Read(#[from] std::io::Error),
+}
+
+/// A bank contained something other than a joltage
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[error("'{0}' is not a battery joltage")]
+pub struct NotADigit(pub char);
+
+pub fn part1(banks: &[BatteryBank]) -> u32 {
+    banks
+        .iter()
+        .enumerate()
+        .map(|(i, bank)| {
+            let joltage = bank.max_joltage();
+            info!(target: "explain", "bank {}: maximum joltage is {joltage}", i + 1);
+            joltage
+        })
+        .sum()
+}
+
+pub fn part2(banks: &[BatteryBank]) -> Result<u64, Error> {
+    banks
+        .iter()
+        .enumerate()
+        .map(|(i, bank)| {
+            info!(target: "explain", "bank {}: {bank}", i + 1);
+            let joltage = bank.max_joltage_2().ok_or(Error::ShortBank {
+                line: i + 1,
+                batteries: bank.cells.len(),
+            })?;
+            info!(target: "explain", "bank {}: maximum joltage is {joltage}", i + 1);
+            Ok(joltage)
+        })
+        .sum()
+}
+
+/// how many batteries are turned on in each bank in part 2
+pub const PART2_BATTERIES: usize = 12;
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatteryBank {
+    cells: Vec<u8>,
+}
+
+impl Display for BatteryBank {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for cell in self.cells.iter() {
+            write!(f, "{cell}")?;
+        }
+        Ok(())
+    }
+}
+
+impl BatteryBank {
+    #[instrument(ret, level = "debug")]
+    pub fn max_joltage(&self) -> u32 {
+        let Some((_last_element, first_elements)) = self.cells.split_last() else {
+            warn!("Unable to split last");
+            return 0;
+        };
+        let (first_digit, first_position) =
+            first_elements
+                .iter()
+                .enumerate()
+                .fold((0, 0), |acc: (u8, usize), (i, item)| {
+                    let mut max = acc.0;
+                    let mut position = acc.1;
+                    if *item > max {
+                        max = *item;
+                        position = i;
+                    }
+                    (max, position)
+                });
+        debug!(?first_digit, ?first_position);
+        let starting_pos = first_position + 1;
+        let remaining = &self.cells[starting_pos..];
+        let Some(second_digit) = remaining.iter().max() else {
+            warn!("Unable to find max of remaining digits");
+            return 0;
+        };
+        first_digit as u32 * 10 + *second_digit as u32
+    }
+
+    /// `None` if the bank has fewer than [`PART2_BATTERIES`] batteries
+    pub fn max_joltage_2(&self) -> Option<u64> {
+        let mut total: u64 = 0;
+        let mut pos = 0;
+        for n in (0..PART2_BATTERIES).rev() {
+            debug!("finding max digit from {pos} to -{n}");
+            let (digit, found_position) = find_max_ignoring_end_n(self.cells.get(pos..)?, n)?;
+            info!(
+                target: "explain",
+                "picked {digit} at position {} from positions {pos}..{}, leaving {n} digits to pick",
+                pos + found_position,
+                self.cells.len() - n
+            );
+            total += 10_u64.pow(n as u32) * digit as u64;
+            pos += found_position + 1;
+        }
+        Some(total)
+    }
+}
+
+/// The first largest digit that leaves at least `end_n` digits after it, and
+/// its position. `None` if there are no more than `end_n` digits
+#[instrument(ret, level = "debug")]
+pub fn find_max_ignoring_end_n(cells: &[u8], end_n: usize) -> Option<(u8, usize)> {
+    let end = cells.len().checked_sub(end_n).filter(|&end| end > 0)?;
+    let (first_elements, _last_elements) = cells.split_at(end);
+    debug!(?first_elements);
+    let (first_digit, position) =
+        first_elements
+            .iter()
+            .enumerate()
+            .fold((0, 0), |acc: (u8, usize), (i, item)| {
+                debug!(?acc, i, item);
+                let mut max = acc.0;
+                let mut position = acc.1;
+                if *item > max {
+                    max = *item;
+                    position = i;
+                }
+                (max, position)
+            });
+    debug!(?first_digit, ?position);
+    let pos = position;
+    Some((first_digit, pos))
+}
+
+common::declare_build_id!();
+
+/// Checks every bank in the input, reporting all problems at once
+pub fn lint(input: &str) -> Vec<Problem> {
+    Lint::lint(input)
+}
+
+/// [`lint`] a line at a time, for streamed input
+#[derive(Debug, Default)]
+pub struct Lint(Vec<Problem>);
+
+impl LineLint for Lint {
+    fn line(&mut self, number: usize, line: &str) {
+        for (x, ch) in line.chars().enumerate() {
+            if !ch.is_ascii_digit() {
+                self.0.push(Problem::at(
+                    number,
+                    x + 1,
+                    format!("'{ch}' is not a battery joltage"),
+                ));
+            }
+        }
+        let batteries = line.chars().count();
+        if batteries < PART2_BATTERIES {
+            self.0.push(Problem::new(
+                number,
+                format!("bank has {batteries} batteries, at least {PART2_BATTERIES} are needed"),
+            ));
+        }
+    }
+
+    fn finish(self) -> Vec<Problem> {
+        self.0
+    }
+}
+
+impl FromStr for BatteryBank {
+    type Err = NotADigit;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells = s
+            .chars()
+            .map(|ch| {
+                ch.to_digit(10)
+                    .map(|digit| digit as u8)
+                    .ok_or(NotADigit(ch))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { cells })
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<BatteryBank>, Error> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse().map_err(|source| Error::InvalidBank {
+                line: i + 1,
+                source,
+            })
+        })
+        .collect()
+}
+
+/// Reads the banks a line at a time, without the whole input having to be
+/// in memory
+#[cfg(feature = "std")]
+pub fn parse_lines<S: AsRef<str>>(
+    lines: impl IntoIterator<Item = std::io::Result<S>>,
+) -> Result<Vec<BatteryBank>, Error> {
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            line?.as_ref().parse().map_err(|source| Error::InvalidBank {
+                line: i + 1,
+                source,
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "plugin")]
+common::declare_plugin! {
+    day: 3,
+    parsed: Vec<BatteryBank>,
+    parse: |input| Ok(parse(input)?),
+    part1: |banks: &Vec<BatteryBank>| Ok(part1(banks).to_string()),
+    part2: |banks: &Vec<BatteryBank>| Ok(part2(banks)?.to_string()),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    const TEST_INPUT: &str = "987654321111111
+811111111111119
+234234234234278
+818181911112111";
+
+    #[test]
+    fn test_part1() {
+        let batteries = parse(TEST_INPUT).expect("Failed to parse");
+        assert_eq!(98, batteries[0].max_joltage());
+        assert_eq!(89, batteries[1].max_joltage());
+        assert_eq!(78, batteries[2].max_joltage());
+        assert_eq!(92, batteries[3].max_joltage());
+    }
+
+    #[test]
+    fn test_part2() {
+        let batteries = parse(TEST_INPUT).expect("Failed to parse");
+        assert_eq!(Some(987654321111), batteries[0].max_joltage_2());
+        assert_eq!(Some(811111111119), batteries[1].max_joltage_2());
+        assert_eq!(Some(434234234278), batteries[2].max_joltage_2());
+        assert_eq!(Some(888911112111), batteries[3].max_joltage_2());
+    }
+
+    #[test]
+    fn test_parse() {
+        let batteries = parse(TEST_INPUT).expect("Failed to parse");
+        assert_eq!(4, batteries.len());
+        assert_eq!(9, batteries[0].cells[0]);
+        assert_eq!(2, batteries[0].cells[7]);
+        assert!(matches!(
+            parse("987654321111111\n98x"),
+            Err(Error::InvalidBank {
+                line: 2,
+                source: NotADigit('x')
+            })
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_parse_lines() {
+        let banks = parse_lines(TEST_INPUT.lines().map(Ok)).expect("Failed to parse");
+        assert_eq!(parse(TEST_INPUT).expect("Failed to parse"), banks);
+        let err = parse_lines(["987654321111111", "98x"].map(Ok)).expect_err("should fail");
+        assert!(matches!(
+            err,
+            Error::InvalidBank {
+                line: 2,
+                source: NotADigit('x')
+            }
+        ));
+        let banks = parse_lines(["987654321111111", "12345"].map(Ok)).expect("Failed to parse");
+        assert!(matches!(
+            part2(&banks),
+            Err(Error::ShortBank {
+                line: 2,
+                batteries: 5
+            })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let banks = parse(TEST_INPUT).expect("Failed to parse");
+        common::testing::assert_round_trip(&banks);
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(lint(TEST_INPUT).is_empty());
+        let problems = lint("98765432111x1111\n12345\n");
+        assert_eq!(
+            vec![
+                "line 1, column 12: 'x' is not a battery joltage",
+                "line 2: bank has 5 batteries, at least 12 are needed",
+            ],
+            problems.iter().map(|p| p.to_string()).collect::<Vec<_>>()
+        );
+    }
+}
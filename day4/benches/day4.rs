@@ -1,20 +1,31 @@
+use std::sync::LazyLock;
+
+use common::settings::DaySettings;
 use day4::Map;
 
 fn main() {
     divan::main();
 }
 
-#[divan::bench]
+/// what divan uses when a benchmark doesn't set a sample count
+const DEFAULT_SAMPLE_COUNT: u32 = 100;
+
+static SETTINGS: LazyLock<DaySettings> =
+    LazyLock::new(|| common::settings::load_day(4, &[]).expect("Failed to load settings"));
+
+#[divan::bench(sample_count = SETTINGS.sample_count("parser", DEFAULT_SAMPLE_COUNT))]
 fn parser(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read input");
+    let input =
+        std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read input");
     bencher.bench(|| {
         let _map = Map::parse(&input).expect("failed to parse input");
     })
 }
 
-#[divan::bench]
+#[divan::bench(sample_count = SETTINGS.sample_count("part1", DEFAULT_SAMPLE_COUNT))]
 fn part1(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read input");
+    let input =
+        std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read input");
 
     bencher.bench(|| {
         let map = Map::parse(&input).expect("Failed to parse input");
@@ -22,19 +33,30 @@ fn part1(bencher: divan::Bencher) {
     })
 }
 
-#[divan::bench]
+#[divan::bench(sample_count = SETTINGS.sample_count("part2", DEFAULT_SAMPLE_COUNT))]
 fn part2(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read input");
-
-    bencher.bench(|| {
+    let input =
+        std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read input");
+    // report throughput in cells examined rather than just time
+    let (_, counters) = common::metrics::measure(|| {
         let mut map = Map::parse(&input).expect("Failed to parse input");
-        let _nlocs = map.part2();
-    })
+        map.part2()
+    });
+
+    bencher
+        .counter(divan::counter::ItemsCount::new(
+            counters["cells re-examined"],
+        ))
+        .bench(|| {
+            let mut map = Map::parse(&input).expect("Failed to parse input");
+            let _nlocs = map.part2();
+        })
 }
 
-#[divan::bench(sample_count = 20)]
+#[divan::bench(sample_count = SETTINGS.sample_count("part2_iterate", 20))]
 fn part2_iterate(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read input");
+    let input =
+        std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read input");
 
     bencher.bench(|| {
         let mut map = Map::parse(&input).expect("Failed to parse input");
@@ -1,18 +1,87 @@
-use std::collections::HashSet;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
-use std::collections::VecDeque;
+use alloc::{collections::VecDeque, format, string::String, vec::Vec};
+use core::ops::ControlFlow;
+#[cfg(not(feature = "std"))]
+use hashbrown::HashSet;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
 
+use common::{
+    lint::Problem,
+    stepper::{self, Progress, Stepper},
+};
 use itertools::Itertools;
+use tracing::info;
 use tracing::instrument;
 use tracing::trace;
 
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("Unknown cell '{cell}' at line {line}, column {column}")]
+    UnknownCell {
+        line: usize,
+        column: usize,
+        cell: char,
+    },
+    #[error("Row {line} has {len} cells but the first row has {expected}")]
+    RaggedRow {
+        line: usize,
+        len: usize,
+        expected: usize,
+    },
+}
+
+common::declare_build_id!();
+
+/// Checks the shape of the grid, reporting all problems at once
+pub fn lint(input: &str) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let width = input.lines().next().map_or(0, |line| line.chars().count());
+    for (y, line) in input.lines().enumerate() {
+        let len = line.chars().count();
+        if len != width {
+            problems.push(Problem::new(
+                y + 1,
+                format!("row has {len} cells but the first row has {width}"),
+            ));
+        }
+        for (x, ch) in line.chars().enumerate() {
+            if ch != '.' && ch != '@' {
+                problems.push(Problem::at(y + 1, x + 1, format!("unknown cell '{ch}'")));
+            }
+        }
+    }
+    problems
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     x: usize,
     y: usize,
 }
 
-#[derive(Debug)]
+impl core::fmt::Display for Point {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Point {
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    pub fn y(&self) -> usize {
+        self.y
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Map {
     pub width: usize,
     pub height: usize,
@@ -21,18 +90,35 @@ pub struct Map {
 
 impl Map {
     #[instrument(ret, level = "debug")]
-    pub fn parse(input: &str) -> anyhow::Result<Self> {
+    pub fn parse(input: &str) -> Result<Self, Error> {
         let width = input.lines().count();
         let height = input.lines().next().map_or(0, |line| line.chars().count());
-        let locations = input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .filter_map(move |(x, ch)| (ch == '@').then_some(Point { x, y }))
-            })
-            .collect();
+        let mut locations = HashSet::new();
+        for (y, line) in input.lines().enumerate() {
+            let len = line.chars().count();
+            if len != height {
+                return Err(Error::RaggedRow {
+                    line: y + 1,
+                    len,
+                    expected: height,
+                });
+            }
+            for (x, ch) in line.chars().enumerate() {
+                match ch {
+                    '@' => {
+                        locations.insert(Point { x, y });
+                    }
+                    '.' => {}
+                    cell => {
+                        return Err(Error::UnknownCell {
+                            line: y + 1,
+                            column: x + 1,
+                            cell,
+                        });
+                    }
+                }
+            }
+        }
         Ok(Self {
             width,
             height,
@@ -42,24 +128,41 @@ impl Map {
 
     pub fn part2_iterate(&mut self) -> usize {
         let mut total = 0;
-        loop {
-            let locs = self.find_accessible_locations();
-            if locs.is_empty() {
-                break;
-            }
-            total += locs.len();
+        let mut sum = |_: usize, _: &Map, removed: &HashSet<Point>| {
+            total += removed.len();
+            ControlFlow::Continue(())
+        };
+        let progress = Progress {
+            task: "rounds",
+            total: None,
+        };
+        stepper::run(self, &mut (progress, &mut sum));
+        total
+    }
+
+    /// A single round of `part2_iterate`: removes every currently accessible
+    /// location and returns the removed locations
+    pub fn remove_accessible_locations(&mut self) -> HashSet<Point> {
+        let locs = self.find_accessible_locations();
+        if !locs.is_empty() {
             trace!(?locs, "Removing accessible locations");
             self.locations = &self.locations - &locs;
         }
-        total
+        locs
     }
 
     pub fn part2(&mut self) -> usize {
         // Initialize queue with all initially accessible locations
         let mut queue: VecDeque<_> = self.find_accessible_locations().into_iter().collect();
         let mut removed = HashSet::new();
+        // only list the neighbours when someone is reading the explanation
+        let explain = tracing::enabled!(target: "explain", tracing::Level::INFO);
 
         while let Some(pos) = queue.pop_front() {
+            if !common::progress::tick("removals", removed.len(), None) {
+                break;
+            }
+            common::metrics::count("cells re-examined", 1);
             // Skip if already removed
             if removed.contains(&pos) {
                 continue;
@@ -70,14 +173,25 @@ impl Map {
             self.locations.remove(&pos);
 
             // Check all neighbors - they might have just become accessible
+            let mut newly_accessible = explain.then(Vec::default);
             for neighbor in self.adjacent_positions(pos) {
                 if !removed.contains(&neighbor)
                     && self.locations.contains(&neighbor)
                     && self.is_accessible(neighbor)
                 {
                     queue.push_back(neighbor);
+                    if let Some(newly_accessible) = &mut newly_accessible {
+                        newly_accessible.push(neighbor);
+                    }
                 }
             }
+            if let Some(newly_accessible) = newly_accessible {
+                info!(
+                    target: "explain",
+                    "removed {pos}; newly accessible neighbours: {}",
+                    newly_accessible.iter().join(" ")
+                );
+            }
         }
 
         removed.len()
@@ -86,7 +200,7 @@ impl Map {
     fn is_accessible(&self, point: Point) -> bool {
         self.adjacent_positions(point)
             .iter()
-            .filter(|p| self.locations.contains(p))
+            .filter(|p| self.locations.contains(*p))
             .count()
             < 4
     }
@@ -118,13 +232,13 @@ impl Map {
             .collect()
     }
 
-    pub fn _print_accessible_locations(&self) -> String {
+    /// Draws the map, marking `highlighted` locations with an `x`
+    pub fn render(&self, highlighted: &HashSet<Point>) -> String {
         let mut output = String::with_capacity((self.width + 1) * self.height);
-        let locs = self.find_accessible_locations();
         for y in 0..self.height {
             for x in 0..self.width {
                 let point = Point { x, y };
-                if locs.contains(&point) {
+                if highlighted.contains(&point) {
                     output.push('x');
                 } else if self.locations.contains(&point) {
                     output.push('@');
@@ -136,12 +250,45 @@ impl Map {
                 }
             }
         }
+        output
+    }
+
+    pub fn _print_accessible_locations(&self) -> String {
+        let locs = self.find_accessible_locations();
+        let mut output = self.render(&locs);
         output.push('\n');
         output.push_str(&format!("Total Accessible Locations: {}", locs.len()));
         output
     }
 }
 
+/// A round of `part2_iterate` per step, with the removed locations as the
+/// delta
+impl Stepper for Map {
+    type Delta = HashSet<Point>;
+
+    fn step(&mut self) -> Option<HashSet<Point>> {
+        let locs = self.remove_accessible_locations();
+        (!locs.is_empty()).then_some(locs)
+    }
+
+    fn is_done(&self) -> bool {
+        !self
+            .locations
+            .iter()
+            .any(|point| self.is_accessible(*point))
+    }
+}
+
+#[cfg(feature = "plugin")]
+common::declare_plugin! {
+    day: 4,
+    parsed: Map,
+    parse: |input| Ok(Map::parse(input)?),
+    part1: |map: &Map| Ok(map.find_accessible_locations().len().to_string()),
+    part2: |map: &Map| Ok(map.clone().part2().to_string()),
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -165,6 +312,22 @@ mod test {
         assert_eq!(10, map.width);
         assert_eq!(10, map.height);
         assert_eq!(71, map.locations.len());
+        assert_eq!(
+            Err(Error::UnknownCell {
+                line: 2,
+                column: 2,
+                cell: 'x'
+            }),
+            Map::parse("..\n.x\n").map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::RaggedRow {
+                line: 2,
+                len: 3,
+                expected: 2
+            }),
+            Map::parse("..\n...\n").map(|_| ())
+        );
     }
 
     #[test]
@@ -174,4 +337,75 @@ mod test {
         assert_eq!(13, locs.len());
         println!("{}", map._print_accessible_locations());
     }
+
+    #[test]
+    fn test_remove_accessible_locations() {
+        let mut map = Map::parse(EXAMPLE_INPUT).expect("Failed to parse input");
+        let removed = map.remove_accessible_locations();
+        assert_eq!(13, removed.len());
+        assert_eq!(58, map.locations.len());
+        let rendered = map.render(&removed);
+        assert_eq!(13, rendered.matches('x').count());
+        assert_eq!(58, rendered.matches('@').count());
+        assert_eq!(10, rendered.lines().count());
+    }
+
+    #[test]
+    fn test_stepper() {
+        let mut map = Map::parse(EXAMPLE_INPUT).expect("Failed to parse input");
+        let mut removed = Vec::new();
+        let mut record = |_: usize, _: &Map, locs: &HashSet<Point>| {
+            removed.push(locs.len());
+            ControlFlow::Continue(())
+        };
+        assert_eq!(
+            3,
+            stepper::run(&mut map, &mut (stepper::Limit(3), &mut record))
+        );
+        assert_eq!(vec![13, 12, 7], removed);
+        assert!(!map.is_done());
+        stepper::run(&mut map, &mut ());
+        assert!(map.is_done());
+        assert_eq!(71 - 43, map.locations.len());
+    }
+
+    #[test]
+    fn test_part2() {
+        let mut map = Map::parse(EXAMPLE_INPUT).expect("Failed to parse input");
+        assert_eq!(43, map.part2_iterate());
+        let mut map = Map::parse(EXAMPLE_INPUT).expect("Failed to parse input");
+        assert_eq!(43, map.part2());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_metrics() {
+        let mut map = Map::parse(EXAMPLE_INPUT).expect("Failed to parse input");
+        let (_, counters) = common::metrics::measure(|| map.part2());
+        assert_eq!(Some(&63), counters.get("cells re-examined"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        // part way through peeling
+        let mut map = Map::parse(EXAMPLE_INPUT).expect("Failed to parse input");
+        map.remove_accessible_locations();
+        common::testing::assert_round_trip(&map);
+        let point = Point { x: 3, y: 7 };
+        common::testing::assert_round_trip(&point);
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(lint(EXAMPLE_INPUT).is_empty());
+        let problems = lint("..@@.\n@@@\n.@x@.\n");
+        assert_eq!(
+            vec![
+                "line 2: row has 3 cells but the first row has 5",
+                "line 3, column 3: unknown cell 'x'",
+            ],
+            problems.iter().map(|p| p.to_string()).collect::<Vec<_>>()
+        );
+    }
 }
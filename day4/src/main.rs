@@ -1,8 +1,9 @@
 use day4::*;
 
 fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
-    let input = std::fs::read_to_string("input")?;
+    let (settings, _logging) = common::settings::init(4, &[])?;
+    let input = std::fs::read_to_string(settings.input_path("input"))?;
+    common::lint::check(day4::lint(&input))?;
     let mut map = Map::parse(&input)?;
     let nlocs = map.find_accessible_locations().len();
     println!("Part 1: {nlocs}");
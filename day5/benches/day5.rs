@@ -1,12 +1,18 @@
+use std::sync::LazyLock;
+
+use common::settings::DaySettings;
 use divan::{Bencher, bench};
 
 fn main() {
     divan::main()
 }
 
+static SETTINGS: LazyLock<DaySettings> =
+    LazyLock::new(|| common::settings::load_day(5, &[]).expect("Failed to load settings"));
+
 #[bench]
 fn parse(bencher: Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read file");
+    let input = std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read file");
 
     bencher.bench(|| {
         let _ = day5::Database::from_string(&input).expect("Failed to parse");
@@ -15,7 +21,7 @@ fn parse(bencher: Bencher) {
 
 #[bench]
 fn part1(bencher: Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read file");
+    let input = std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read file");
 
     bencher.bench(|| {
         day5::part1(&input).expect("Failed part 1");
@@ -24,9 +30,12 @@ fn part1(bencher: Bencher) {
 
 #[bench]
 fn part2(bencher: Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read file");
+    let input = std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read file");
+    let (_, counters) = common::metrics::measure(|| day5::part2(&input));
 
-    bencher.bench(|| {
-        day5::part2(&input).expect("Failed part 1");
-    })
+    bencher
+        .counter(divan::counter::ItemsCount::new(counters["ranges merged"]))
+        .bench(|| {
+            day5::part2(&input).expect("Failed part 1");
+        })
 }
@@ -0,0 +1,3 @@
+fn main() {
+    common::build::emit_build_id();
+}
@@ -1,4 +1,15 @@
-use anyhow::anyhow;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::num::ParseIntError;
+
+use common::lint::{LineLint, Problem, position};
 use nom::{
     IResult, Parser,
     bytes::complete::tag,
@@ -6,19 +17,40 @@ use nom::{
     multi::{count, separated_list1},
     sequence::separated_pair,
 };
-use tracing::{instrument, trace};
+use tracing::{info, instrument, trace};
 
-pub fn part1(input: &str) -> anyhow::Result<usize> {
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Unexpected input at line {line}, column {column}")]
+    Syntax { line: usize, column: usize },
+    #[error("Invalid range '{text}' on line {line}")]
+    InvalidRange { line: usize, text: String },
+    #[error("Range {lower}-{upper} on line {line} has its lower bound above its upper bound")]
+    ReversedRange { line: usize, lower: u64, upper: u64 },
+    #[error("Invalid ingredient id '{text}' on line {line}")]
+    InvalidId {
+        line: usize,
+        text: String,
+        #[source]
+        source: ParseIntError,
+    },
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    Read(#[from] std::io::Error),
+}
+
+pub fn part1(input: &str) -> Result<usize, Error> {
     let database = Database::from_string(input)?;
     Ok(database.fresh_ingredients().len())
 }
 
-pub fn part2(input: &str) -> anyhow::Result<u64> {
+pub fn part2(input: &str) -> Result<u64, Error> {
     let database = Database::from_string(input)?;
     Ok(database.fresh_ingredient_ids())
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct Range {
     pub(crate) lower: u64,
     pub(crate) upper: u64,
@@ -34,41 +66,73 @@ impl Range {
     }
 }
 
-#[derive(Debug)]
+impl core::fmt::Display for Range {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}-{}", self.lower, self.upper)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Database {
     pub(crate) fresh: Vec<Range>,
     pub(crate) ingredients: Vec<u64>,
 }
 
 impl Database {
-    pub fn from_string(input: &str) -> anyhow::Result<Self> {
+    pub fn from_string(input: &str) -> Result<Self, Error> {
         parse(input)
     }
 
     pub fn fresh_ingredients(&self) -> Vec<u64> {
         self.ingredients
             .iter()
-            .filter(|&item| self.is_fresh(item))
+            .enumerate()
+            .take_while(|(i, _)| {
+                common::progress::tick("ingredients", *i, Some(self.ingredients.len()))
+            })
+            .map(|(_, item)| item)
+            .filter(|&item| {
+                let range = self.fresh_range(item);
+                match range {
+                    Some(range) => {
+                        info!(target: "explain", "ingredient {item} is fresh (in range {range})")
+                    }
+                    None => info!(target: "explain", "ingredient {item} is spoiled"),
+                }
+                range.is_some()
+            })
             .copied()
             .collect()
     }
-    pub(crate) fn is_fresh(&self, id: &u64) -> bool {
-        self.fresh.iter().any(|range| range.contains(id))
+
+    pub fn is_fresh(&self, id: &u64) -> bool {
+        self.fresh_range(id).is_some()
+    }
+
+    /// the first range that contains the ingredient, if it's fresh
+    fn fresh_range(&self, id: &u64) -> Option<&Range> {
+        self.fresh.iter().find(|range| range.contains(id))
     }
 
     #[instrument(ret, skip(self), level = "debug")]
     fn fresh_ingredient_ids(&self) -> u64 {
         // consolidate fresh ingredient ranges
         let mut consolidated_ranges: Vec<Range> = Vec::default();
-        for range in self.fresh.iter() {
-            let (lowers, uppers): (Vec<_>, Vec<_>) = consolidated_ranges
+        for (i, range) in self.fresh.iter().enumerate() {
+            if !common::progress::tick("ranges", i, Some(self.fresh.len())) {
+                break;
+            }
+            let merged: Vec<Range> = consolidated_ranges
                 .extract_if(.., |consolidated_range| {
                     range.lower <= consolidated_range.upper
                         && range.upper >= consolidated_range.lower
                 })
-                .map(|r| (r.lower, r.upper))
-                .unzip();
+                .collect();
+            let (lowers, uppers): (Vec<_>, Vec<_>) =
+                merged.iter().map(|r| (r.lower, r.upper)).unzip();
             trace!(?range, ?lowers, ?uppers);
+            common::metrics::count("ranges merged", merged.len() as u64);
             if let (Some(lower), Some(upper)) = (lowers.into_iter().min(), uppers.into_iter().max())
             {
                 let adjusted_range = Range {
@@ -80,24 +144,151 @@ impl Database {
                     items = adjusted_range.n_ids(),
                     "Creating a new consolidated range"
                 );
+                info!(
+                    target: "explain",
+                    "range {range} overlaps {}, merged into {adjusted_range}",
+                    merged
+                        .iter()
+                        .map(|r| r.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
                 consolidated_ranges.push(adjusted_range);
             } else {
                 trace!(?range, "Using this range");
+                info!(target: "explain", "range {range} doesn't overlap any earlier range");
                 consolidated_ranges.push(*range)
             }
         }
         trace!(?consolidated_ranges);
+        for range in consolidated_ranges.iter() {
+            info!(target: "explain", "range {range} contains {} ids", range.n_ids());
+        }
         consolidated_ranges.into_iter().map(|r| r.n_ids()).sum()
     }
 }
 
+common::declare_build_id!();
+
+/// Checks the ranges and ingredient ids, reporting all problems at once
+pub fn lint(input: &str) -> Vec<Problem> {
+    Lint::lint(input)
+}
+
+/// [`lint`] a line at a time, for streamed input
+#[derive(Debug, Default)]
+pub struct Lint {
+    problems: Vec<Problem>,
+    /// whether the blank line before the ingredient ids has been seen
+    found_separator: bool,
+}
+
+impl LineLint for Lint {
+    fn line(&mut self, number: usize, line: &str) {
+        if self.found_separator {
+            if line.parse::<u64>().is_err() {
+                self.problems.push(Problem::new(
+                    number,
+                    format!("'{line}' is not an ingredient id"),
+                ));
+            }
+            return;
+        }
+        if line.is_empty() {
+            self.found_separator = true;
+            return;
+        }
+        let Some((lower, upper)) = line
+            .split_once('-')
+            .and_then(|(lower, upper)| lower.parse::<u64>().ok().zip(upper.parse::<u64>().ok()))
+        else {
+            self.problems
+                .push(Problem::new(number, format!("'{line}' is not a range")));
+            return;
+        };
+        if lower > upper {
+            self.problems.push(Problem::new(
+                number,
+                format!("range {lower}-{upper} has its lower bound above its upper bound"),
+            ));
+        }
+    }
+
+    fn finish(mut self) -> Vec<Problem> {
+        if !self.found_separator {
+            self.problems.push(Problem::whole(
+                "no blank line between the ranges and the ingredient ids",
+            ));
+        }
+        self.problems
+    }
+}
+
+/// Solves both parts in a single pass. The ranges have to be read in full,
+/// but the ingredient ids after them are checked one at a time and never
+/// stored. Returns the part 1 and part 2 answers
+#[cfg(feature = "std")]
+pub fn solve_lines<S: AsRef<str>>(
+    lines: impl IntoIterator<Item = std::io::Result<S>>,
+) -> Result<(usize, u64), Error> {
+    let mut lines = lines.into_iter().enumerate();
+    let mut database = Database {
+        fresh: Vec::default(),
+        ingredients: Vec::default(),
+    };
+    for (i, line) in lines.by_ref() {
+        let line = line?;
+        let line = line.as_ref();
+        if line.is_empty() {
+            break;
+        }
+        let (_, range) = nom::combinator::all_consuming(parse_range)
+            .parse(line)
+            .map_err(|_| Error::InvalidRange {
+                line: i + 1,
+                text: line.to_string(),
+            })?;
+        check_range(i, &range)?;
+        database.fresh.push(range);
+    }
+    let mut fresh = 0;
+    for (i, line) in lines {
+        if !common::progress::tick("ingredients", i, None) {
+            break;
+        }
+        let line = line?;
+        let text = line.as_ref();
+        let id = text.parse::<u64>().map_err(|source| Error::InvalidId {
+            line: i + 1,
+            text: text.to_string(),
+            source,
+        })?;
+        if database.is_fresh(&id) {
+            fresh += 1;
+        }
+    }
+    Ok((fresh, database.fresh_ingredient_ids()))
+}
+
 pub(crate) fn parse_range(input: &str) -> IResult<&str, Range> {
     separated_pair(complete::u64, tag("-"), complete::u64)
         .map(|(lower, upper)| Range { lower, upper })
         .parse(input)
 }
 
-pub(crate) fn parse(input: &str) -> anyhow::Result<Database> {
+/// `i` is the range's index, which is also its line number, counting from 0
+fn check_range(i: usize, range: &Range) -> Result<(), Error> {
+    if range.lower > range.upper {
+        return Err(Error::ReversedRange {
+            line: i + 1,
+            lower: range.lower,
+            upper: range.upper,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn parse(input: &str) -> Result<Database, Error> {
     let (_, database) = separated_pair(
         separated_list1(line_ending, parse_range),
         count(line_ending, 2),
@@ -108,10 +299,29 @@ pub(crate) fn parse(input: &str) -> anyhow::Result<Database> {
         ingredients: ids,
     })
     .parse(input)
-    .map_err(|e| anyhow!("Failed to parse: {e}"))?;
+    .map_err(|e| {
+        let remaining = match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+            nom::Err::Incomplete(_) => "",
+        };
+        let (line, column) = position(input, input.len() - remaining.len());
+        Error::Syntax { line, column }
+    })?;
+    for (i, range) in database.fresh.iter().enumerate() {
+        check_range(i, range)?;
+    }
     Ok(database)
 }
 
+#[cfg(feature = "plugin")]
+common::declare_plugin! {
+    day: 5,
+    parsed: Database,
+    parse: |input| Ok(Database::from_string(input)?),
+    part1: |database: &Database| Ok(database.fresh_ingredients().len().to_string()),
+    part2: |database: &Database| Ok(database.fresh_ingredient_ids().to_string()),
+}
+
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
@@ -171,4 +381,71 @@ pub(crate) mod test {
         let fresh = database.fresh_ingredient_ids();
         assert_eq!(14, fresh);
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_metrics() {
+        let database = parse(EXAMPLE_INPUT).expect("Failed to parse");
+        let (_, counters) = common::metrics::measure(|| database.fresh_ingredient_ids());
+        // 12-18 joins 10-14 and 16-20 together
+        assert_eq!(Some(&2), counters.get("ranges merged"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_solve_lines() {
+        assert_eq!(
+            (3, 14),
+            solve_lines(EXAMPLE_INPUT.lines().map(Ok)).expect("Failed to solve")
+        );
+        let err = solve_lines(["3-5", "x-1"].map(Ok)).expect_err("should fail");
+        assert!(matches!(err, Error::InvalidRange { line: 2, .. }));
+        let err = solve_lines(["3-5", "", "4", "y"].map(Ok)).expect_err("should fail");
+        assert!(matches!(err, Error::InvalidId { line: 4, .. }));
+        let err = solve_lines(["3-5", "9-1"].map(Ok)).expect_err("should fail");
+        assert!(matches!(
+            err,
+            Error::ReversedRange {
+                line: 2,
+                lower: 9,
+                upper: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Database::from_string("3-5\n10-x\n\n1\n").expect_err("should fail");
+        assert!(
+            matches!(err, Error::Syntax { line: 2, column: 1 }),
+            "{err:?}"
+        );
+        let err = Database::from_string("3-5\n10-1\n\n1\n").expect_err("should fail");
+        assert!(matches!(err, Error::ReversedRange { line: 2, .. }));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let database = Database::from_string(EXAMPLE_INPUT).expect("Failed to parse");
+        common::testing::assert_round_trip(&database);
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(lint(EXAMPLE_INPUT).is_empty());
+        let problems = lint("3-5\n14-10\nabc\n\n1\n\nx\n");
+        assert_eq!(
+            vec![
+                "line 2: range 14-10 has its lower bound above its upper bound",
+                "line 3: 'abc' is not a range",
+                "line 6: '' is not an ingredient id",
+                "line 7: 'x' is not an ingredient id",
+            ],
+            problems.iter().map(|p| p.to_string()).collect::<Vec<_>>()
+        );
+        let problems = lint("3-5\n");
+        assert_eq!(1, problems.len());
+        assert_eq!(None, problems[0].line);
+    }
 }
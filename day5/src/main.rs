@@ -1,9 +1,11 @@
+use common::input::Input;
+
 fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
-    let input = std::fs::read_to_string("input")?;
-    let res = day5::part1(&input)?;
-    println!("Part 1: {res}");
-    let res = day5::part2(&input)?;
-    println!("Part 2: {res}");
+    let (settings, _logging) = common::settings::init(5, &[])?;
+    let mut input = Input::open(&settings.input_path("input"))?;
+    let (part1, part2) =
+        input.solve_linted::<day5::Lint, _, _>(|lines| day5::solve_lines(lines))?;
+    println!("Part 1: {part1}");
+    println!("Part 2: {part2}");
     Ok(())
 }
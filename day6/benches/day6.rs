@@ -1,27 +1,34 @@
+use std::sync::LazyLock;
+
+use common::settings::DaySettings;
+
 fn main() {
     divan::main()
 }
 
-#[divan::bench(sample_count = 1000)]
+static SETTINGS: LazyLock<DaySettings> =
+    LazyLock::new(|| common::settings::load_day(6, &[]).expect("Failed to load settings"));
+
+#[divan::bench(sample_count = SETTINGS.sample_count("parse", 1000))]
 fn parse(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read file");
+    let input = std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read file");
     bencher.bench(|| _ = day6::parse(&input));
 }
 
-#[divan::bench(sample_count = 1000)]
+#[divan::bench(sample_count = SETTINGS.sample_count("parse2", 1000))]
 fn parse2(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read file");
+    let input = std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read file");
     bencher.bench(|| _ = day6::parse2(&input));
 }
 
-#[divan::bench(sample_count = 1000)]
+#[divan::bench(sample_count = SETTINGS.sample_count("part1", 1000))]
 fn part1(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read file");
+    let input = std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read file");
     bencher.bench(|| day6::part1(&input).expect("Failed part1"));
 }
 
-#[divan::bench(sample_count = 1000)]
+#[divan::bench(sample_count = SETTINGS.sample_count("part2", 1000))]
 fn part2(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read file");
+    let input = std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read file");
     bencher.bench(|| day6::part2(&input).expect("Failed part2"));
 }
@@ -1,3 +1,13 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Display, iter::Iterator};
 use itertools::Itertools;
 use nom::{
     IResult, Parser,
@@ -6,22 +16,47 @@ use nom::{
     multi::separated_list1,
     sequence::{delimited, pair, separated_pair},
 };
-use std::iter::Iterator;
-use tracing::{debug, instrument};
+use tracing::{debug, info, instrument};
 
-pub fn part1(input: &str) -> anyhow::Result<String> {
-    let (_, problems) = parse(input).expect("parsing failed");
-    let sum: i64 = problems.into_iter().map(|problem| problem.compute()).sum();
-    Ok(sum.to_string())
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("Unexpected input at line {line}, column {column}")]
+    Syntax { line: usize, column: usize },
+    #[error("Unknown operator '{operator}' in column {column}")]
+    UnknownOperator { column: usize, operator: char },
 }
 
-pub fn part2(input: &str) -> anyhow::Result<String> {
-    let problems = parse2(input);
-    let sum: i64 = problems.into_iter().map(|problem| problem.compute()).sum();
-    Ok(sum.to_string())
+pub fn part1(input: &str) -> Result<String, Error> {
+    let (_, problems) = parse(input).map_err(|e| {
+        let remaining = match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+            nom::Err::Incomplete(_) => "",
+        };
+        let (line, column) = common::lint::position(input, input.len() - remaining.len());
+        Error::Syntax { line, column }
+    })?;
+    Ok(sum(&problems).to_string())
 }
 
-#[derive(Debug)]
+pub fn part2(input: &str) -> Result<String, Error> {
+    let problems = parse2(input)?;
+    Ok(sum(&problems).to_string())
+}
+
+fn sum(problems: &[Problem]) -> i64 {
+    problems
+        .iter()
+        .enumerate()
+        .map(|(i, problem)| {
+            let result = problem.compute();
+            info!(target: "explain", "problem {}: {problem} = {result}", i + 1);
+            result
+        })
+        .sum()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operation {
     Add,
     Multiply,
@@ -43,12 +78,23 @@ where
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Problem {
     args: Vec<i64>,
     op: Operation,
 }
 
+impl Display for Problem {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let op = match self.op {
+            Operation::Add => " + ",
+            Operation::Multiply => " * ",
+        };
+        write!(f, "{}", self.args.iter().join(op))
+    }
+}
+
 impl Problem {
     #[instrument(ret, level = "trace")]
     pub fn compute(&self) -> i64 {
@@ -59,51 +105,130 @@ impl Problem {
     }
 }
 
+common::declare_build_id!();
+
+/// Checks the worksheet, reporting all problems at once
+pub fn lint(input: &str) -> Vec<common::lint::Problem> {
+    let mut problems = Vec::new();
+    let lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect::<Vec<_>>();
+    let Some(((op_line, ops), rows)) = lines.split_last() else {
+        problems.push(common::lint::Problem::whole("worksheet is empty"));
+        return problems;
+    };
+    // the columns that each whitespace-separated token starts at
+    let columns = |line: &str| {
+        line.char_indices()
+            .filter(|&(i, ch)| {
+                !ch.is_whitespace() && line[..i].chars().last().is_none_or(char::is_whitespace)
+            })
+            .map(|(i, _)| line[..i].chars().count() + 1)
+            .collect::<Vec<_>>()
+    };
+    let operators = ops.split_whitespace().collect::<Vec<_>>();
+    for (token, column) in operators.iter().zip(columns(ops)) {
+        if *token != "*" && *token != "+" {
+            problems.push(common::lint::Problem::at(
+                op_line + 1,
+                column,
+                format!("unknown operator '{token}'"),
+            ));
+        }
+    }
+    for (y, row) in rows {
+        let numbers = row.split_whitespace().collect::<Vec<_>>();
+        for (token, column) in numbers.iter().zip(columns(row)) {
+            if token.parse::<i64>().is_err() {
+                problems.push(common::lint::Problem::at(
+                    y + 1,
+                    column,
+                    format!("'{token}' is not a number"),
+                ));
+            }
+        }
+        if numbers.len() < operators.len() {
+            problems.push(common::lint::Problem::new(
+                y + 1,
+                format!(
+                    "row has {} numbers but there are {} operators",
+                    numbers.len(),
+                    operators.len()
+                ),
+            ));
+        }
+    }
+    problems
+}
+
 #[instrument(ret, level = "trace")]
-pub fn parse2(input: &str) -> Vec<Problem> {
-    let chariters = input.lines().map(|l| l.chars()).collect::<Vec<_>>();
+pub fn parse2(input: &str) -> Result<Vec<Problem>, Error> {
+    // some editors strip trailing whitespace, so pad all lines to the same
+    // width. Otherwise the columns past the end of the shortest line would be
+    // lost
+    let width = input.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+    let chariters = input
+        .lines()
+        .map(|l| l.chars().pad_using(width, |_| ' '))
+        .collect::<Vec<_>>();
     // zip the same column of each line into a vector. Essentially transposing
     // between lines and columns
     let cols = Multizip(chariters).collect::<Vec<_>>();
     debug!(?cols);
-    let problems = cols
+    let columns = cols
         .iter()
+        .enumerate()
         // process the line from the back to the front
         .rev()
-        .map(|col| {
+        .map(|(x, col)| {
             debug!(?col);
-            (
-                // we know the operator is in the last row, so construct a
-                // string of the characters in the first lines...
+            // the last line holds the column's Operator (if there is one)...
+            let op = match col.iter().last() {
+                Some('*') => Some(Operation::Multiply),
+                Some('+') => Some(Operation::Add),
+                Some(' ') | None => None,
+                Some(&operator) => {
+                    return Err(Error::UnknownOperator {
+                        column: x + 1,
+                        operator,
+                    });
+                }
+            };
+            Ok((
+                x,
+                // ...so construct a number from the characters in the
+                // first lines
                 col.iter()
                     .take(col.len() - 1)
                     .collect::<String>()
                     .trim()
                     .parse::<i64>(),
-                // ...and convert the last line into an Operator (if there is one)
-                col.iter().last().and_then(|c| match c {
-                    '*' => Some(Operation::Multiply),
-                    '+' => Some(Operation::Add),
-                    ' ' => None,
-                    _ => panic!("Unexpected operation"),
-                }),
-            )
+                op,
+            ))
         })
+        .collect::<Result<Vec<_>, _>>()?;
+    let problems = columns
+        .into_iter()
         // Process numbers until we get totally a blank column (indicated by a
-        // ParseIntError), and construct a Problem object from them
+        // ParseIntError), and construct a Problem object from them. Blank
+        // columns before a problem's first number, like the ones left by
+        // trailing whitespace, are skipped
         .batching(|it| {
             let mut problemop: Option<Operation> = None;
             let mut args: Vec<i64> = Vec::default();
-            loop {
-                let Some((iparseresult, op)) = it.next() else {
-                    break;
-                };
+            for (x, iparseresult, op) in it.by_ref() {
                 debug!(?iparseresult, ?op);
                 if let Some(oper) = op {
                     problemop = Some(oper);
                 }
                 match iparseresult {
-                    Ok(n) => args.push(n),
+                    Ok(n) => {
+                        info!(target: "explain", "column {} forms the number {n}", x + 1);
+                        args.push(n)
+                    }
+                    Err(_e) if args.is_empty() && problemop.is_none() => continue,
                     Err(_e) => break,
                 }
             }
@@ -113,7 +238,7 @@ pub fn parse2(input: &str) -> Vec<Problem> {
         })
         .collect::<Vec<_>>();
     debug!(?problems);
-    problems
+    Ok(problems)
 }
 
 #[instrument(ret, level = "trace")]
@@ -145,6 +270,16 @@ pub fn parse(input: &str) -> IResult<&str, Vec<Problem>> {
     .parse(input)
 }
 
+// the two parts read the worksheet differently, so just hold on to the input
+#[cfg(feature = "plugin")]
+common::declare_plugin! {
+    day: 6,
+    parsed: String,
+    parse: |input: &str| Ok(input.to_string()),
+    part1: |input: &String| Ok(part1(input)?),
+    part2: |input: &String| Ok(part2(input)?),
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -168,11 +303,61 @@ mod test {
 
     #[test]
     fn test_parse2() {
-        let problems = parse2(EXAMPLE_INPUT);
+        let problems = parse2(EXAMPLE_INPUT).expect("parsing failed");
         assert_eq!(4, problems.len());
         assert_eq!(1058, problems[0].compute());
         assert_eq!(3253600, problems[1].compute());
         assert_eq!(625, problems[2].compute());
         assert_eq!(8544, problems[3].compute());
     }
+
+    #[test]
+    fn test_parse2_trimmed_lines() {
+        let trimmed = EXAMPLE_INPUT
+            .lines()
+            .map(|l| l.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(part2(EXAMPLE_INPUT).unwrap(), part2(&trimmed).unwrap());
+        // and lines with more whitespace than the others
+        let padded = EXAMPLE_INPUT.replacen('\n', "  \n", 1);
+        assert_eq!(part2(EXAMPLE_INPUT).unwrap(), part2(&padded).unwrap());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Err(Error::Syntax { line: 3, column: 1 }),
+            part1("1 2\n3 4\n/ -\n")
+        );
+        assert_eq!(
+            Err(Error::UnknownOperator {
+                column: 3,
+                operator: '-'
+            }),
+            part2("1 2\n3 4\n/ -\n")
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let problems = parse2(EXAMPLE_INPUT).expect("parsing failed");
+        common::testing::assert_round_trip(&problems);
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(lint(EXAMPLE_INPUT).is_empty());
+        let problems = lint("123 328  51 64\n 45 64  387\n  6 9x  215 314\n*   +   /   +\n");
+        assert_eq!(
+            vec![
+                "line 4, column 9: unknown operator '/'",
+                "line 2: row has 3 numbers but there are 4 operators",
+                "line 3, column 5: '9x' is not a number",
+            ],
+            problems.iter().map(|p| p.to_string()).collect::<Vec<_>>()
+        );
+        assert_eq!(1, lint("\n").len());
+    }
 }
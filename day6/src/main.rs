@@ -1,6 +1,7 @@
 fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
-    let input = std::fs::read_to_string("input")?;
+    let (settings, _logging) = common::settings::init(6, &[])?;
+    let input = std::fs::read_to_string(settings.input_path("input"))?;
+    common::lint::check(day6::lint(&input))?;
     println!("Part 1: {}", day6::part1(&input)?);
     println!("Part 2: {}", day6::part2(&input)?);
     Ok(())
@@ -1,18 +1,24 @@
+use std::sync::LazyLock;
+
+use common::settings::DaySettings;
 use day7::Manifold;
 
 fn main() {
     divan::main()
 }
 
+static SETTINGS: LazyLock<DaySettings> =
+    LazyLock::new(|| common::settings::load_day(7, &[]).expect("Failed to load settings"));
+
 #[divan::bench]
 fn parse(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read file");
+    let input = std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read file");
     bencher.bench(|| Manifold::parse(&input).expect("failed to parse"))
 }
 
 #[divan::bench]
 fn part1(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read file");
+    let input = std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read file");
     bencher.bench(|| {
         let mut manifold = Manifold::parse(&input).expect("failed to parse");
         let _ = manifold.run();
@@ -21,9 +27,18 @@ fn part1(bencher: divan::Bencher) {
 
 #[divan::bench]
 fn part2(bencher: divan::Bencher) {
-    let input = std::fs::read_to_string("input").expect("Failed to read file");
-    bencher.bench(|| {
-        let mut manifold = Manifold::parse(&input).expect("failed to parse");
-        let _ = manifold.timelines();
-    })
+    let input = std::fs::read_to_string(SETTINGS.input_path("input")).expect("Failed to read file");
+    let (_, counters) = common::metrics::measure(|| {
+        Manifold::parse(&input)
+            .expect("failed to parse")
+            .timelines()
+    });
+    bencher
+        .counter(divan::counter::ItemsCount::new(
+            counters["timeline positions"],
+        ))
+        .bench(|| {
+            let mut manifold = Manifold::parse(&input).expect("failed to parse");
+            let _ = manifold.timelines();
+        })
 }
@@ -1,24 +1,86 @@
-use std::{
-    collections::{HashMap, HashSet},
-    ops::Add,
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::ops::{Add, ControlFlow};
+#[cfg(not(feature = "std"))]
+use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+use common::{
+    lint::Problem,
+    stepper::{self, Progress, Stepper},
 };
+use tracing::{info, instrument, trace};
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("Didn't find start")]
+    MissingStart,
+    #[error("Unknown cell '{cell}' at line {line}, column {column}")]
+    UnknownCell {
+        line: usize,
+        column: usize,
+        cell: char,
+    },
+}
 
-use anyhow::anyhow;
-use tracing::{instrument, trace};
+common::declare_build_id!();
+
+/// Checks the manifold diagram, reporting all problems at once
+pub fn lint(input: &str) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let width = input.lines().next().map_or(0, |line| line.chars().count());
+    let mut start: Option<(usize, usize)> = None;
+    for (y, line) in input.lines().enumerate() {
+        let len = line.chars().count();
+        if len != width {
+            problems.push(Problem::new(
+                y + 1,
+                format!("row has {len} cells but the first row has {width}"),
+            ));
+        }
+        for (x, ch) in line.chars().enumerate() {
+            match ch {
+                '.' | '^' => {}
+                'S' => match start {
+                    Some((sy, sx)) => problems.push(Problem::at(
+                        y + 1,
+                        x + 1,
+                        format!("another start 'S', the first is at line {sy}, column {sx}"),
+                    )),
+                    None => start = Some((y + 1, x + 1)),
+                },
+                _ => problems.push(Problem::at(y + 1, x + 1, format!("unknown cell '{ch}'"))),
+            }
+        }
+    }
+    if start.is_none() {
+        problems.push(Problem::whole("no start 'S'"));
+    }
+    problems
+}
 
-pub fn part1(input: &str) -> anyhow::Result<String> {
+pub fn part1(input: &str) -> Result<String, Error> {
     let mut manifold = Manifold::parse(input)?;
     let splits = manifold.run();
     Ok(splits.to_string())
 }
 
-pub fn part2(input: &str) -> anyhow::Result<String> {
+pub fn part2(input: &str) -> Result<String, Error> {
     let mut manifold = Manifold::parse(input)?;
     let timelines = manifold.timelines();
     Ok(timelines.to_string())
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     x: i32,
     y: i32,
@@ -41,6 +103,7 @@ const LEFT: Point = Point { x: -1, y: 0 };
 const RIGHT: Point = Point { x: 1, y: 0 };
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Symbol {
     Start,
     Splitter,
@@ -48,6 +111,7 @@ pub enum Symbol {
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Manifold {
     data: Vec<Vec<char>>,
     start: Point,
@@ -56,25 +120,34 @@ pub struct Manifold {
 
 impl Manifold {
     #[instrument(ret, skip(input), level = "debug")]
-    pub fn parse(input: &str) -> anyhow::Result<Self> {
+    pub fn parse(input: &str) -> Result<Self, Error> {
         let data = input
             .lines()
             .map(|line| line.chars().collect::<Vec<_>>())
             .collect::<Vec<_>>();
         trace!(?data);
-        let start = (|| {
-            for (y, line) in data.iter().enumerate() {
-                for (x, ch) in line.iter().enumerate() {
-                    if ch == &'S' {
-                        return Ok(Point {
+        let mut start = None;
+        for (y, line) in data.iter().enumerate() {
+            for (x, ch) in line.iter().enumerate() {
+                match ch {
+                    '.' | '^' => {}
+                    'S' => {
+                        start.get_or_insert(Point {
                             x: x as i32,
                             y: y as i32,
                         });
                     }
+                    &cell => {
+                        return Err(Error::UnknownCell {
+                            line: y + 1,
+                            column: x + 1,
+                            cell,
+                        });
+                    }
                 }
             }
-            Err(anyhow!("Didn't find start"))
-        })()?;
+        }
+        let start = start.ok_or(Error::MissingStart)?;
         Ok(Manifold {
             data,
             start,
@@ -102,6 +175,36 @@ impl Manifold {
         }
     }
 
+    pub fn start(&self) -> Point {
+        self.start
+    }
+
+    /// the beams that are currently travelling through the manifold
+    pub fn beams(&self) -> &HashSet<Point> {
+        &self.beams
+    }
+
+    /// Draws the manifold with a `|` at every point in `beams`
+    pub fn render<'a>(&self, beams: impl IntoIterator<Item = &'a Point>) -> String {
+        let mut data = self.data.clone();
+        for beam in beams {
+            if let Some(ch) = data
+                .get_mut(beam.y as usize)
+                .and_then(|line| line.get_mut(beam.x as usize))
+                && *ch == '.'
+            {
+                *ch = '|';
+            }
+        }
+        data.into_iter()
+            .map(|line| {
+                line.into_iter()
+                    .chain(core::iter::once('\n'))
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
     #[instrument(ret, skip(self), level = "trace")]
     pub fn step_beam(&mut self) -> Option<u64> {
         if self.beams.is_empty() {
@@ -109,15 +212,15 @@ impl Manifold {
             self.beams.insert(self.start);
             return Some(0);
         }
+        if self.is_done() {
+            return None;
+        }
         let mut splits = 0;
         let mut beams = HashSet::default();
-        std::mem::swap(&mut beams, &mut self.beams);
+        core::mem::swap(&mut beams, &mut self.beams);
         for beam in beams {
             trace!(?beam, "processing beam");
             let next_loc = beam + DOWN;
-            if next_loc.y >= self.height() {
-                return None;
-            }
             let sym = self.at(&next_loc);
             trace!(?next_loc, ?sym);
             if sym == Symbol::Splitter {
@@ -134,14 +237,31 @@ impl Manifold {
 
     pub fn run(&mut self) -> u64 {
         let mut total_splits = 0;
-        while let Some(splits) = self.step_beam() {
+        let mut explain = |row: usize, _: &Manifold, splits: &u64| {
             total_splits += splits;
-        }
+            if *splits > 0 {
+                info!(
+                    target: "explain",
+                    "row {row}: beams split {splits} times, {total_splits} so far"
+                );
+            }
+            ControlFlow::Continue(())
+        };
+        let progress = self.progress();
+        stepper::run(self, &mut (progress, &mut explain));
         total_splits
     }
 
+    /// Ticks once for every row that the beams or timelines pass through
+    fn progress(&self) -> Progress {
+        Progress {
+            task: "rows",
+            total: self.height().try_into().ok(),
+        }
+    }
+
     #[instrument(ret, skip(self))]
-    pub fn step_timeline(&self, timelines: Timelines) -> Option<Timelines> {
+    pub fn step_timeline(&self, timelines: &Timelines) -> Option<Timelines> {
         let mut new_timelines = Timelines::default();
         for (cur_point, multiples) in timelines.data.iter() {
             let next_point = *cur_point + DOWN;
@@ -155,27 +275,91 @@ impl Manifold {
                 new_timelines.insert(next_point, *multiples)
             }
         }
+        common::metrics::count("timeline positions", new_timelines.data.len() as u64);
+        common::metrics::count("timeline rows", 1);
         Some(new_timelines)
     }
 
+    /// Steps a single timeline from the start down through the manifold
+    pub fn propagate(&self) -> Propagation<'_> {
+        let mut timelines = Timelines::default();
+        timelines.insert(self.start, 1);
+        Propagation {
+            manifold: self,
+            timelines,
+        }
+    }
+
     #[instrument(ret, skip(self))]
     pub fn timelines(&mut self) -> u64 {
-        let mut ntimelines = 1;
-        let mut timelines = {
-            let mut t = Timelines::default();
-            t.insert(self.start, 1);
-            t
+        let mut propagation = self.propagate();
+        let mut explain = |step: usize, state: &Propagation, new: &u64| {
+            if *new > 0 {
+                let n = state.timelines().count();
+                info!(
+                    target: "explain",
+                    "row {}: {} timelines became {n} across {} positions",
+                    step + 1,
+                    n - new,
+                    state.timelines().data.len()
+                );
+            }
+            ControlFlow::Continue(())
         };
-        while let Some(new_timelines) = self.step_timeline(timelines) {
-            ntimelines = new_timelines.data.values().sum();
-            timelines = new_timelines;
-        }
-        ntimelines
+        stepper::run(&mut propagation, &mut (self.progress(), &mut explain));
+        propagation.timelines().count()
+    }
+}
+
+/// A row of beams per step, with the number of splits as the delta
+impl Stepper for Manifold {
+    type Delta = u64;
+
+    fn step(&mut self) -> Option<u64> {
+        self.step_beam()
+    }
+
+    fn is_done(&self) -> bool {
+        self.beams.iter().any(|beam| beam.y + 1 >= self.height())
+    }
+}
+
+/// Timelines moving down through a [`Manifold`], one row per step
+#[derive(Debug)]
+pub struct Propagation<'a> {
+    manifold: &'a Manifold,
+    timelines: Timelines,
+}
+
+impl Propagation<'_> {
+    pub fn timelines(&self) -> &Timelines {
+        &self.timelines
+    }
+}
+
+/// The delta is the number of timelines that the step added
+impl Stepper for Propagation<'_> {
+    type Delta = u64;
+
+    fn step(&mut self) -> Option<u64> {
+        let next = self.manifold.step_timeline(&self.timelines)?;
+        let added = next.count() - self.timelines.count();
+        self.timelines = next;
+        Some(added)
+    }
+
+    fn is_done(&self) -> bool {
+        self.timelines
+            .data
+            .keys()
+            .any(|point| point.y >= self.manifold.height())
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Timelines {
+    #[cfg_attr(feature = "serde", serde(with = "point_counts"))]
     pub data: HashMap<Point, u64>,
 }
 
@@ -187,6 +371,45 @@ impl Timelines {
             .and_modify(|val| *val += num)
             .or_insert(num);
     }
+
+    /// the number of timelines across all points
+    pub fn count(&self) -> u64 {
+        self.data.values().sum()
+    }
+}
+
+/// JSON maps can only have string keys, so the counts are stored as a list
+/// of `(point, count)` pairs instead
+#[cfg(feature = "serde")]
+mod point_counts {
+    use alloc::vec::Vec;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{HashMap, Point};
+
+    pub fn serialize<S: Serializer>(
+        data: &HashMap<Point, u64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(data)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Point, u64>, D::Error> {
+        let pairs = Vec::<(Point, u64)>::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+#[cfg(feature = "plugin")]
+common::declare_plugin! {
+    day: 7,
+    parsed: Manifold,
+    parse: |input| Ok(Manifold::parse(input)?),
+    part1: |manifold: &Manifold| Ok(manifold.clone().run().to_string()),
+    part2: |manifold: &Manifold| Ok(manifold.clone().timelines().to_string()),
 }
 
 #[cfg(test)]
@@ -216,6 +439,15 @@ mod test {
         let manifold = Manifold::parse(EXAMPLE_INPUT).expect("Failed to parse");
         assert_eq!(15, manifold.width());
         assert_eq!(16, manifold.height());
+        assert_eq!(Err(Error::MissingStart), Manifold::parse("...\n.^.\n"));
+        assert_eq!(
+            Err(Error::UnknownCell {
+                line: 2,
+                column: 3,
+                cell: 'x'
+            }),
+            Manifold::parse(".S.\n.^x\n")
+        );
         assert_eq!(Point { x: 7, y: 0 }, manifold.start);
         assert_eq!(Symbol::Splitter, manifold.at(&Point { x: 1, y: 14 }));
     }
@@ -226,9 +458,98 @@ mod test {
         assert_eq!(21, manifold.run());
     }
 
+    #[test]
+    fn test_render() {
+        let mut manifold = Manifold::parse(EXAMPLE_INPUT).expect("Failed to parse");
+        for _ in 0..3 {
+            manifold.step_beam();
+        }
+        assert_eq!(2, manifold.beams().len());
+        let rendered = manifold.render(manifold.beams());
+        let lines = rendered.lines().collect::<Vec<_>>();
+        assert_eq!(16, lines.len());
+        assert_eq!(".......S.......", lines[0]);
+        assert_eq!("...............", lines[1]);
+        assert_eq!("......|^|......", lines[2]);
+    }
+
     #[test]
     fn test_part2() {
         let mut manifold = Manifold::parse(EXAMPLE_INPUT).expect("Failed to parse");
         assert_eq!(40, manifold.timelines());
     }
+
+    #[test]
+    fn test_steppers() {
+        let mut manifold = Manifold::parse(EXAMPLE_INPUT).expect("Failed to parse");
+        // the start and then one row at a time until the beams reach the bottom
+        assert_eq!(16, stepper::run(&mut manifold, &mut ()));
+        assert!(manifold.is_done());
+        assert_eq!(None, manifold.step());
+
+        let manifold = Manifold::parse(EXAMPLE_INPUT).expect("Failed to parse");
+        let mut propagation = manifold.propagate();
+        let mut added = 0;
+        let mut count = |_: usize, _: &Propagation, new: &u64| {
+            added += new;
+            ControlFlow::Continue(())
+        };
+        assert_eq!(
+            5,
+            stepper::run(&mut propagation, &mut (stepper::Limit(5), &mut count))
+        );
+        assert_eq!(4, propagation.timelines().count());
+        assert_eq!(3, added);
+        assert!(!propagation.is_done());
+        stepper::run(&mut propagation, &mut ());
+        assert!(propagation.is_done());
+        assert_eq!(40, propagation.timelines().count());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_metrics() {
+        let mut manifold = Manifold::parse(EXAMPLE_INPUT).expect("Failed to parse");
+        let (_, counters) = common::metrics::measure(|| manifold.timelines());
+        assert_eq!(Some(&90), counters.get("timeline positions"));
+        assert_eq!(Some(&16), counters.get("timeline rows"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut manifold = Manifold::parse(EXAMPLE_INPUT).expect("Failed to parse");
+        for _ in 0..5 {
+            manifold.step_beam();
+        }
+        let mut timelines = Timelines::default();
+        timelines.insert(manifold.start, 1);
+        for _ in 0..5 {
+            timelines = manifold.step_timeline(&timelines).expect("ran off the end");
+        }
+        common::testing::assert_round_trip(&manifold);
+        common::testing::assert_round_trip(&timelines);
+        common::testing::assert_round_trip(&Symbol::Splitter);
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(lint(EXAMPLE_INPUT).is_empty());
+        let problems = lint("..S..\n..^.\n.S.x.\n");
+        assert_eq!(
+            vec![
+                "line 2: row has 4 cells but the first row has 5",
+                "line 3, column 2: another start 'S', the first is at line 1, column 3",
+                "line 3, column 4: unknown cell 'x'",
+            ],
+            problems.iter().map(|p| p.to_string()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["no start 'S'".to_string()],
+            lint("...\n")
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -1,8 +1,9 @@
 use day7::{part1, part2};
 
 fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
-    let input = std::fs::read_to_string("input")?;
+    let (settings, _logging) = common::settings::init(7, &[])?;
+    let input = std::fs::read_to_string(settings.input_path("input"))?;
+    common::lint::check(day7::lint(&input))?;
     println!("Part 1: {}", part1(&input)?);
     println!("Part 2: {}", part2(&input)?);
     Ok(())